@@ -0,0 +1,147 @@
+use crate::ast::{Enum, Variant};
+use crate::expand::{call_site_ident, fields_pat};
+use crate::unraw::MemberUnraw;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt as _;
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    input.attrs.accessors?;
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let methods = input
+        .variants
+        .iter()
+        .map(|variant| variant_accessors(&ty, variant));
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+// The other inherent methods the derive may generate, which an accessor must
+// not be named after.
+const GENERATED_METHODS: &[&str] = &[
+    "error_id",
+    "exit_code",
+    "grpc_code",
+    "http_status",
+    "into_source",
+    "io_kind",
+    "is_retryable",
+    "location",
+    "locations",
+    "msg",
+    "origin_location",
+    "set_location",
+    "thread",
+    "timestamp",
+    "with_location",
+];
+
+// The is_*, as_* and into_* accessors of a variant.
+pub(crate) fn accessor_names(variant: &Variant) -> [Ident; 3] {
+    let ident = &variant.ident;
+    let snake = snake_case(ident);
+    [
+        format_ident!("is_{}", snake, span = ident.span()),
+        format_ident!("as_{}", snake, span = ident.span()),
+        format_ident!("into_{}", snake, span = ident.span()),
+    ]
+}
+
+// The first accessor of the variant that has the name of another generated
+// method.
+pub(crate) fn conflicting_accessor(variant: &Variant) -> Option<Ident> {
+    accessor_names(variant)
+        .into_iter()
+        .find(|name| GENERATED_METHODS.iter().any(|method| name == method))
+}
+
+fn variant_accessors(ty: &Ident, variant: &Variant) -> TokenStream {
+    let ident = &variant.ident;
+    let [is, as_ref, into] = accessor_names(variant);
+
+    let pat = fields_pat(&variant.fields);
+    let vars: Vec<Ident> = variant
+        .fields
+        .iter()
+        .map(|field| match &field.member {
+            MemberUnraw::Named(ident) => ident.to_local(),
+            MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+        })
+        .collect();
+    let types: Vec<_> = variant.fields.iter().map(|field| field.ty).collect();
+    let (ref_ty, owned_ty, value) = match types.as_slice() {
+        [single] => (quote!(&#single), quote!(#single), quote!(#(#vars)*)),
+        _ => (
+            quote!((#(&#types,)*)),
+            quote!((#(#types,)*)),
+            quote!((#(#vars,)*)),
+        ),
+    };
+
+    let is_doc = format!("Returns true if this is a `{}::{}`.", ty, ident);
+    let as_doc = format!("Returns the fields of a `{}::{}`, by reference.", ty, ident);
+    let into_doc = format!(
+        "Returns the fields of a `{}::{}`, or the error itself if it is another variant.",
+        ty, ident,
+    );
+
+    quote! {
+        #[doc = #is_doc]
+        #[allow(deprecated)]
+        pub fn #is(&self) -> bool {
+            ::core::matches!(self, #ty::#ident {..})
+        }
+
+        #[doc = #as_doc]
+        #[allow(deprecated, unreachable_patterns, unused_variables)]
+        pub fn #as_ref(&self) -> ::core::option::Option<#ref_ty> {
+            match self {
+                #ty::#ident #pat => ::core::option::Option::Some(#value),
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        #[doc = #into_doc]
+        #[allow(deprecated, unreachable_patterns, unused_variables)]
+        pub fn #into(self) -> ::core::result::Result<#owned_ty, Self> {
+            match self {
+                #ty::#ident #pat => ::core::result::Result::Ok(#value),
+                other => ::core::result::Result::Err(other),
+            }
+        }
+    }
+}
+
+// MyVariant -> my_variant, HTTPError -> http_error, Utf8Error -> utf8_error.
+pub(crate) fn snake_case(ident: &Ident) -> String {
+    let name = ident.unraw().to_string();
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let boundary = match prev {
+                Some('_') => false,
+                Some(prev) if prev.is_lowercase() || prev.is_ascii_digit() => true,
+                Some(_) => next.map_or(false, |next| next.is_lowercase()),
+                None => false,
+            };
+            if boundary {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
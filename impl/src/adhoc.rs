@@ -0,0 +1,70 @@
+use crate::ast::Enum;
+use crate::expand::{call_site_ident, captured_initializers, init_initializers};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned as _;
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    let variant = input.adhoc_variant()?;
+    let message_field = variant.message_field()?;
+    let span = variant.attrs.adhoc.unwrap().original.span();
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variant_ident = &variant.ident;
+    let captures = variant.captures();
+    let init = init_initializers(&captures);
+    let captured = captured_initializers(&captures);
+    let message_member = &message_field.member;
+    let message = quote_spanned!(message_field.ty.span()=> ::core::convert::From::from(message));
+    let body = quote! {
+        #ty::#variant_ident {
+            #init
+            #message_member: #message,
+            #captured
+        }
+    };
+
+    let from_impl = |from: TokenStream| {
+        quote_spanned! {span=>
+            #[allow(
+                deprecated,
+                unused_qualifications,
+                clippy::elidable_lifetime_names,
+                clippy::needless_lifetimes,
+                clippy::redundant_field_names,
+                clippy::useless_conversion,
+            )]
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<#from> for #ty #ty_generics #where_clause {
+                #[track_caller]
+                fn from(message: #from) -> Self {
+                    #body
+                }
+            }
+        }
+    };
+    let from_str = from_impl(quote!(&'static str));
+    let from_string = from_impl(quote!(::std::string::String));
+
+    Some(quote! {
+        #[allow(
+            deprecated,
+            unused_qualifications,
+            clippy::redundant_field_names,
+            clippy::useless_conversion,
+        )]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// Creates the ad-hoc variant of this error with the given message.
+            #[track_caller]
+            pub fn msg<M: ::core::fmt::Display>(message: M) -> Self {
+                let message = ::std::string::ToString::to_string(&message);
+                #body
+            }
+        }
+
+        #from_str
+        #from_string
+    })
+}
@@ -0,0 +1,60 @@
+use crate::ast::{Enum, Struct};
+use crate::attr::Attrs;
+use crate::expand::call_site_ident;
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned as _;
+use syn::Generics;
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let ty = call_site_ident(&input.ident);
+    impl_assertions(&ty, input.generics, &input.attrs)
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    // With #[error(boxed)], check the newtype that callers actually see.
+    let ty = input
+        .boxed_ident()
+        .unwrap_or_else(|| call_site_ident(&input.ident));
+    impl_assertions(&ty, input.generics, &input.attrs)
+}
+
+fn impl_assertions(ty: &Ident, generics: &Generics, attrs: &Attrs) -> Option<TokenStream> {
+    if attrs.max_size.is_none() && attrs.assert.is_none() {
+        return None;
+    }
+
+    // Type parameters are rejected during validation; lifetimes don't change
+    // the layout, so check the 'static instantiation.
+    let lifetimes = generics.lifetimes().map(|_| quote!('static));
+    let concrete = quote!(#ty<#(#lifetimes),*>);
+
+    let max_size = attrs.max_size.as_ref().map(|max_size| {
+        let size = &max_size.size;
+        let message = format!(
+            "`{}` is larger than #[error(max_size = {})] allows",
+            ty, size,
+        );
+        quote_spanned! {size.span()=>
+            const _: () = ::core::assert!(
+                ::core::mem::size_of::<#concrete>() <= #size,
+                #message,
+            );
+        }
+    });
+
+    let bounds = attrs.assert.iter().flat_map(|assert| &assert.bounds);
+    let trait_assertions = bounds.map(|bound| {
+        quote_spanned! {bound.span()=>
+            const _: fn() = || {
+                fn error_must_implement<T: ?::core::marker::Sized + #bound>() {}
+                error_must_implement::<#concrete>();
+            };
+        }
+    });
+
+    Some(quote! {
+        #max_size
+        #(#trait_assertions)*
+    })
+}
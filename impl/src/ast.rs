@@ -5,6 +5,7 @@ use proc_macro2::Span;
 use std::fmt::{self, Display};
 use syn::{
     Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index, Result, Type,
+    Visibility,
 };
 
 pub enum Input<'a> {
@@ -21,6 +22,7 @@ pub struct Struct<'a> {
 
 pub struct Enum<'a> {
     pub attrs: Attrs<'a>,
+    pub vis: &'a Visibility,
     pub ident: Ident,
     pub generics: &'a Generics,
     pub variants: Vec<Variant<'a>>,
@@ -68,11 +70,12 @@ impl<'a> Struct<'a> {
     fn from_syn(node: &'a DeriveInput, data: &'a DataStruct) -> Result<Self> {
         let mut attrs = attr::get(&node.attrs)?;
         let scope = ParamsInScope::new(&node.generics);
-        let fields = Field::multiple_from_syn(&data.fields, &scope)?;
+        let mut fields = Field::multiple_from_syn(&data.fields, &scope)?;
+        let container = ContainerKind::from_struct(data);
         if let Some(display) = &mut attrs.display {
-            let container = ContainerKind::from_struct(data);
             display.expand_shorthand(&fields, container)?;
         }
+        expand_diagnostic_shorthand(&mut attrs, &mut fields, container)?;
         Ok(Struct {
             attrs,
             ident: node.ident.clone(),
@@ -99,15 +102,29 @@ impl<'a> Enum<'a> {
                     variant.attrs.transparent = attrs.transparent;
                     variant.attrs.fmt.clone_from(&attrs.fmt);
                 }
+                if variant.attrs.code.is_none() {
+                    variant.attrs.code.clone_from(&attrs.code);
+                }
+                if variant.attrs.help.is_none() {
+                    variant.attrs.help.clone_from(&attrs.help);
+                }
+                if variant.attrs.url.is_none() {
+                    variant.attrs.url.clone_from(&attrs.url);
+                }
+                if variant.attrs.severity.is_none() {
+                    variant.attrs.severity.clone_from(&attrs.severity);
+                }
+                let container = ContainerKind::from_variant(node);
                 if let Some(display) = &mut variant.attrs.display {
-                    let container = ContainerKind::from_variant(node);
                     display.expand_shorthand(&variant.fields, container)?;
                 }
+                expand_diagnostic_shorthand(&mut variant.attrs, &mut variant.fields, container)?;
                 Ok(variant)
             })
             .collect::<Result<_>>()?;
         Ok(Enum {
             attrs,
+            vis: &node.vis,
             ident: node.ident.clone(),
             generics: &node.generics,
             variants,
@@ -153,6 +170,26 @@ impl<'a> Field<'a> {
     }
 }
 
+fn expand_diagnostic_shorthand(
+    attrs: &mut Attrs,
+    fields: &mut [Field],
+    container: ContainerKind,
+) -> Result<()> {
+    if let Some(help) = &mut attrs.help {
+        help.expand_shorthand(fields, container)?;
+    }
+    if let Some(url) = &mut attrs.url {
+        url.expand_shorthand(fields, container)?;
+    }
+    for i in 0..fields.len() {
+        if let Some(mut text) = fields[i].attrs.label.as_ref().and_then(|l| l.text.clone()) {
+            text.expand_shorthand(fields, container)?;
+            fields[i].attrs.label.as_mut().unwrap().text = Some(text);
+        }
+    }
+    Ok(())
+}
+
 impl ContainerKind {
     fn from_struct(node: &DataStruct) -> Self {
         match node.fields {
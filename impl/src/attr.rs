@@ -2,10 +2,10 @@ use proc_macro2::{Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream,
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
 use syn::parse::discouraged::Speculative;
-use syn::parse::{End, ParseStream};
+use syn::parse::{End, Lookahead1, Parse, ParseStream, Peek};
 use syn::{
     braced, bracketed, parenthesized, token, Attribute, Error, ExprPath, Ident, Index, LitFloat,
-    LitInt, LitStr, Meta, Result, Token,
+    LitInt, LitStr, Meta, Result, Token, TypeParamBound, Visibility,
 };
 
 pub struct Attrs<'a> {
@@ -13,10 +13,36 @@ pub struct Attrs<'a> {
     pub source: Option<Source<'a>>,
     pub backtrace: Option<&'a Attribute>,
     pub location: Option<&'a Attribute>,
+    pub context: Option<&'a Attribute>,
     pub from: Option<From<'a>>,
     pub transparent: Option<Transparent<'a>>,
     pub fmt: Option<Fmt<'a>>,
     pub debug: Option<DebugFallback<'a>>,
+    pub code: Option<Code<'a>>,
+    pub help: Option<Display<'a>>,
+    pub url: Option<Display<'a>>,
+    pub severity: Option<Severity<'a>>,
+    pub diagnostic: Option<&'a Attribute>,
+    pub status: Option<Status<'a>>,
+    pub into_response: Option<&'a Attribute>,
+    pub grpc: Option<Grpc<'a>>,
+    pub exit_code: Option<ExitCode<'a>>,
+    pub retryable: Option<Retryable<'a>>,
+    pub io_kind: Option<IoKind<'a>>,
+    pub into_io: Option<&'a Attribute>,
+    pub accessors: Option<&'a Attribute>,
+    pub ext: Option<&'a Attribute>,
+    pub display_with: Option<DisplayWith<'a>>,
+    pub boxed: Option<Boxed<'a>>,
+    pub max_size: Option<MaxSize<'a>>,
+    pub assert: Option<Assert<'a>>,
+    pub capture: Option<Capture<'a>>,
+    pub init: Option<Init<'a>>,
+    pub id: Option<Id<'a>>,
+    pub adhoc: Option<Adhoc<'a>>,
+    pub label: Option<Label<'a>>,
+    pub source_code: Option<&'a Attribute>,
+    pub related: Option<&'a Attribute>,
 }
 
 #[derive(Clone)]
@@ -42,6 +68,7 @@ pub struct From<'a> {
     pub original: &'a Attribute,
     pub span: Span,
     pub no_source: bool,
+    pub try_from: bool,
 }
 
 #[derive(Copy, Clone)]
@@ -62,6 +89,107 @@ pub struct Fmt<'a> {
     pub path: ExprPath,
 }
 
+#[derive(Clone)]
+pub struct Code<'a> {
+    pub original: &'a Attribute,
+    pub value: LitStr,
+}
+
+#[derive(Clone)]
+pub struct Severity<'a> {
+    pub original: &'a Attribute,
+    pub level: Ident,
+}
+
+#[derive(Clone)]
+pub struct Status<'a> {
+    pub original: &'a Attribute,
+    pub code: LitInt,
+}
+
+#[derive(Clone)]
+pub struct Grpc<'a> {
+    pub original: &'a Attribute,
+    pub code: Ident,
+}
+
+#[derive(Clone)]
+pub struct ExitCode<'a> {
+    pub original: &'a Attribute,
+    pub code: LitInt,
+}
+
+#[derive(Clone)]
+pub struct Retryable<'a> {
+    pub original: &'a Attribute,
+    pub expr: TokenStream,
+}
+
+#[derive(Clone)]
+pub struct IoKind<'a> {
+    pub original: &'a Attribute,
+    pub kind: Ident,
+}
+
+#[derive(Clone)]
+pub struct Boxed<'a> {
+    pub original: &'a Attribute,
+    pub vis: Visibility,
+    pub name: Option<Ident>,
+}
+
+#[derive(Clone)]
+pub struct MaxSize<'a> {
+    pub original: &'a Attribute,
+    pub size: LitInt,
+}
+
+#[derive(Clone)]
+pub struct Assert<'a> {
+    pub original: &'a Attribute,
+    pub bounds: Vec<TypeParamBound>,
+}
+
+#[derive(Copy, Clone)]
+pub struct Capture<'a> {
+    pub original: &'a Attribute,
+    pub kind: CaptureKind,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum CaptureKind {
+    Thread,
+    Time,
+}
+
+#[derive(Clone)]
+pub struct Init<'a> {
+    pub original: &'a Attribute,
+    pub expr: TokenStream,
+}
+
+#[derive(Copy, Clone)]
+pub struct Id<'a> {
+    pub original: &'a Attribute,
+}
+
+#[derive(Copy, Clone)]
+pub struct Adhoc<'a> {
+    pub original: &'a Attribute,
+}
+
+#[derive(Clone)]
+pub struct DisplayWith<'a> {
+    pub original: &'a Attribute,
+    pub path: ExprPath,
+}
+
+#[derive(Clone)]
+pub struct Label<'a> {
+    pub original: &'a Attribute,
+    pub text: Option<Display<'a>>,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum Trait {
     Debug,
@@ -81,10 +209,36 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
         source: None,
         backtrace: None,
         location: None,
+        context: None,
         from: None,
         transparent: None,
         fmt: None,
         debug: None,
+        code: None,
+        help: None,
+        url: None,
+        severity: None,
+        diagnostic: None,
+        status: None,
+        into_response: None,
+        grpc: None,
+        exit_code: None,
+        retryable: None,
+        io_kind: None,
+        into_io: None,
+        accessors: None,
+        ext: None,
+        display_with: None,
+        boxed: None,
+        max_size: None,
+        assert: None,
+        capture: None,
+        init: None,
+        id: None,
+        adhoc: None,
+        label: None,
+        source_code: None,
+        related: None,
     };
 
     for attr in input {
@@ -114,23 +268,34 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
                 return Err(Error::new_spanned(attr, "duplicate #[location] attribute"));
             }
             attrs.location = Some(attr);
+        } else if attr.path().is_ident("context") {
+            attr.meta.require_path_only()?;
+            if attrs.context.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[context] attribute"));
+            }
+            attrs.context = Some(attr);
         } else if attr.path().is_ident("from") {
             if attrs.from.is_some() {
                 return Err(Error::new_spanned(attr, "duplicate #[from] attribute"));
             }
-            // Support optional flags: #[from(no_source)]
+            // Support optional flags: #[from(no_source, try_from)]
             let mut no_source = false;
+            let mut try_from = false;
             match &attr.meta {
                 Meta::Path(_) => {}
                 Meta::List(_) => {
                     mod kw {
                         syn::custom_keyword!(no_source);
+                        syn::custom_keyword!(try_from);
                     }
                     attr.parse_args_with(|input: ParseStream| {
                         while !input.is_empty() {
                             if input.peek(kw::no_source) {
                                 let _ = input.parse::<kw::no_source>()?;
                                 no_source = true;
+                            } else if input.peek(kw::try_from) {
+                                let _ = input.parse::<kw::try_from>()?;
+                                try_from = true;
                             } else {
                                 return Err(input.error("unsupported option in #[from(...)]"));
                             }
@@ -150,6 +315,7 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
                 original: attr,
                 span,
                 no_source,
+                try_from,
             });
         }
     }
@@ -158,17 +324,84 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
 }
 
 fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Result<()> {
+    attr.parse_args_with(|input: ParseStream| {
+        let lookahead = input.lookahead1();
+        if !lookahead.peek(LitStr) {
+            return parse_error_options(attrs, attr, input, lookahead);
+        }
+
+        let fmt = input.parse::<LitStr>()?;
+        let args = if input.is_empty() || input.peek(Token![,]) && input.peek2(End) {
+            input.parse::<Option<Token![,]>>()?;
+            TokenStream::new()
+        } else {
+            parse_token_expr(input, false)?
+        };
+
+        let requires_fmt_machinery = !args.is_empty();
+
+        let display = Display {
+            original: attr,
+            fmt,
+            args,
+            requires_fmt_machinery,
+            has_bonus_display: false,
+            infinite_recursive: false,
+            implied_bounds: Set::new(),
+            bindings: Vec::new(),
+        };
+        if attrs.display.is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "only one #[error(...)] attribute is allowed",
+            ));
+        }
+        attrs.display = Some(display);
+        Ok(())
+    })
+}
+
+// Comma-separated keyword options, e.g. #[error(transparent)] or
+// #[error(code = "app::parse", severity = Warning)].
+fn parse_error_options<'a, 'b>(
+    attrs: &mut Attrs<'a>,
+    attr: &'a Attribute,
+    input: ParseStream<'b>,
+    mut lookahead: Lookahead1<'b>,
+) -> Result<()> {
     mod kw {
         syn::custom_keyword!(transparent);
         syn::custom_keyword!(fmt);
         syn::custom_keyword!(debug);
+        syn::custom_keyword!(code);
+        syn::custom_keyword!(help);
+        syn::custom_keyword!(url);
+        syn::custom_keyword!(severity);
+        syn::custom_keyword!(status);
+        syn::custom_keyword!(grpc);
+        syn::custom_keyword!(exit_code);
+        syn::custom_keyword!(retryable);
+        syn::custom_keyword!(io_kind);
+        syn::custom_keyword!(into_io);
+        syn::custom_keyword!(accessors);
+        syn::custom_keyword!(boxed);
+        syn::custom_keyword!(max_size);
+        syn::custom_keyword!(assert);
+        syn::custom_keyword!(capture);
+        syn::custom_keyword!(init);
+        syn::custom_keyword!(id);
+        syn::custom_keyword!(adhoc);
+        syn::custom_keyword!(ext);
+        syn::custom_keyword!(display_with);
+        syn::custom_keyword!(diagnostic);
+        syn::custom_keyword!(into_response);
+        syn::custom_keyword!(label);
+        syn::custom_keyword!(source_code);
+        syn::custom_keyword!(related);
     }
 
-    attr.parse_args_with(|input: ParseStream| {
-        let lookahead = input.lookahead1();
-        let fmt = if lookahead.peek(LitStr) {
-            input.parse::<LitStr>()?
-        } else if lookahead.peek(kw::transparent) {
+    loop {
+        if lookahead.peek(kw::transparent) {
             let kw: kw::transparent = input.parse()?;
             if attrs.transparent.is_some() {
                 return Err(Error::new_spanned(
@@ -180,7 +413,6 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
                 original: attr,
                 span: kw.span,
             });
-            return Ok(());
         } else if lookahead.peek(kw::fmt) {
             input.parse::<kw::fmt>()?;
             input.parse::<Token![=]>()?;
@@ -195,7 +427,6 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
                 original: attr,
                 path,
             });
-            return Ok(());
         } else if lookahead.peek(kw::debug) {
             let kw: kw::debug = input.parse()?;
             if attrs.debug.is_some() {
@@ -208,39 +439,490 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
                 original: attr,
                 span: kw.span,
             });
-            return Ok(());
+        } else if lookahead.peek(kw::code) {
+            let kw: kw::code = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = if input.peek(LitStr) {
+                input.parse::<LitStr>()?
+            } else {
+                let path: syn::Path = input.parse()?;
+                let repr = path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                LitStr::new(&repr, kw.span)
+            };
+            if attrs.code.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(code = ...)] attribute",
+                ));
+            }
+            attrs.code = Some(Code {
+                original: attr,
+                value,
+            });
+        } else if peek_gated(&lookahead, input, kw::help, "miette") {
+            let kw: kw::help = input.parse()?;
+            require_feature(kw.span, "help = ...", "miette")?;
+            input.parse::<Token![=]>()?;
+            if attrs.help.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(help = ...)] attribute",
+                ));
+            }
+            attrs.help = Some(display_from_lit(attr, input.parse()?));
+        } else if peek_gated(&lookahead, input, kw::url, "miette") {
+            let kw: kw::url = input.parse()?;
+            require_feature(kw.span, "url = ...", "miette")?;
+            input.parse::<Token![=]>()?;
+            if attrs.url.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(url = ...)] attribute",
+                ));
+            }
+            attrs.url = Some(display_from_lit(attr, input.parse()?));
+        } else if peek_gated(&lookahead, input, kw::severity, "miette") {
+            let kw: kw::severity = input.parse()?;
+            require_feature(kw.span, "severity = ...", "miette")?;
+            input.parse::<Token![=]>()?;
+            let level: Ident = input.parse()?;
+            if level != "Error" && level != "Warning" && level != "Advice" {
+                return Err(Error::new_spanned(
+                    level,
+                    "expected one of `Error`, `Warning`, `Advice`",
+                ));
+            }
+            if attrs.severity.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(severity = ...)] attribute",
+                ));
+            }
+            attrs.severity = Some(Severity {
+                original: attr,
+                level,
+            });
+        } else if lookahead.peek(kw::status) {
+            input.parse::<kw::status>()?;
+            input.parse::<Token![=]>()?;
+            let code: LitInt = input.parse()?;
+            match code.base10_parse::<u16>() {
+                Ok(100..=999) => {}
+                _ => {
+                    return Err(Error::new_spanned(
+                        code,
+                        "expected a three-digit HTTP status code",
+                    ));
+                }
+            }
+            if attrs.status.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(status = ...)] attribute",
+                ));
+            }
+            attrs.status = Some(Status {
+                original: attr,
+                code,
+            });
+        } else if peek_gated(&lookahead, input, kw::grpc, "tonic") {
+            let kw: kw::grpc = input.parse()?;
+            require_feature(kw.span, "grpc = ...", "tonic")?;
+            input.parse::<Token![=]>()?;
+            let code = if input.peek(LitStr) {
+                let lit: LitStr = input.parse()?;
+                Ident::new(&lit.value(), lit.span())
+            } else {
+                input.parse::<Ident>()?
+            };
+            if !GRPC_CODES.iter().any(|known| code == known) {
+                return Err(Error::new_spanned(
+                    code,
+                    "expected a tonic::Code variant, such as `NotFound` or `Internal`",
+                ));
+            }
+            if attrs.grpc.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(grpc = ...)] attribute",
+                ));
+            }
+            attrs.grpc = Some(Grpc {
+                original: attr,
+                code,
+            });
+        } else if lookahead.peek(kw::exit_code) {
+            input.parse::<kw::exit_code>()?;
+            input.parse::<Token![=]>()?;
+            let code = if input.peek(LitStr) {
+                let name: LitStr = input.parse()?;
+                match SYSEXITS.iter().find(|(known, _)| name.value() == *known) {
+                    Some((_, value)) => LitInt::new(&value.to_string(), name.span()),
+                    None => {
+                        return Err(Error::new_spanned(
+                            name,
+                            "expected a sysexits name, such as \"EX_USAGE\" or \"EX_CONFIG\"",
+                        ));
+                    }
+                }
+            } else {
+                let code: LitInt = input.parse()?;
+                if code.base10_parse::<u8>().is_err() {
+                    return Err(Error::new_spanned(
+                        code,
+                        "expected an exit code between 0 and 255",
+                    ));
+                }
+                code
+            };
+            if attrs.exit_code.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(exit_code = ...)] attribute",
+                ));
+            }
+            attrs.exit_code = Some(ExitCode {
+                original: attr,
+                code,
+            });
+        } else if lookahead.peek(kw::retryable) {
+            let kw: kw::retryable = input.parse()?;
+            let expr = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                input.parse::<syn::Expr>()?.into_token_stream()
+            } else {
+                quote_spanned!(kw.span=> true)
+            };
+            if attrs.retryable.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(retryable)] attribute",
+                ));
+            }
+            attrs.retryable = Some(Retryable {
+                original: attr,
+                expr,
+            });
+        } else if lookahead.peek(kw::io_kind) {
+            input.parse::<kw::io_kind>()?;
+            input.parse::<Token![=]>()?;
+            let kind: Ident = input.parse()?;
+            if attrs.io_kind.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(io_kind = ...)] attribute",
+                ));
+            }
+            attrs.io_kind = Some(IoKind {
+                original: attr,
+                kind,
+            });
+        } else if lookahead.peek(kw::into_io) {
+            input.parse::<kw::into_io>()?;
+            if attrs.into_io.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(into_io)] attribute",
+                ));
+            }
+            attrs.into_io = Some(attr);
+        } else if lookahead.peek(kw::accessors) {
+            input.parse::<kw::accessors>()?;
+            if attrs.accessors.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(accessors)] attribute",
+                ));
+            }
+            attrs.accessors = Some(attr);
+        } else if lookahead.peek(kw::boxed) {
+            input.parse::<kw::boxed>()?;
+            let (vis, name) = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                (input.parse::<Visibility>()?, Some(input.parse::<Ident>()?))
+            } else {
+                (Visibility::Inherited, None)
+            };
+            if attrs.boxed.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(boxed)] attribute",
+                ));
+            }
+            attrs.boxed = Some(Boxed {
+                original: attr,
+                vis,
+                name,
+            });
+        } else if lookahead.peek(kw::max_size) {
+            input.parse::<kw::max_size>()?;
+            input.parse::<Token![=]>()?;
+            let size: LitInt = input.parse()?;
+            size.base10_parse::<usize>()?;
+            if attrs.max_size.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(max_size = ...)] attribute",
+                ));
+            }
+            attrs.max_size = Some(MaxSize {
+                original: attr,
+                size,
+            });
+        } else if lookahead.peek(kw::assert) {
+            input.parse::<kw::assert>()?;
+            let content;
+            parenthesized!(content in input);
+            let bounds = content.parse_terminated(TypeParamBound::parse, Token![,])?;
+            if attrs.assert.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(assert(...))] attribute",
+                ));
+            }
+            attrs.assert = Some(Assert {
+                original: attr,
+                bounds: bounds.into_iter().collect(),
+            });
+        } else if lookahead.peek(kw::capture) {
+            input.parse::<kw::capture>()?;
+            input.parse::<Token![=]>()?;
+            let ident: Ident = input.parse()?;
+            let kind = if ident == "thread" {
+                CaptureKind::Thread
+            } else if ident == "time" {
+                CaptureKind::Time
+            } else {
+                return Err(Error::new_spanned(
+                    ident,
+                    "expected `thread` or `time` in #[error(capture = ...)]",
+                ));
+            };
+            if attrs.capture.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(capture = ...)] attribute",
+                ));
+            }
+            attrs.capture = Some(Capture {
+                original: attr,
+                kind,
+            });
+        } else if lookahead.peek(kw::init) {
+            input.parse::<kw::init>()?;
+            input.parse::<Token![=]>()?;
+            let expr = input.parse::<syn::Expr>()?.into_token_stream();
+            if attrs.init.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(init = ...)] attribute",
+                ));
+            }
+            attrs.init = Some(Init {
+                original: attr,
+                expr,
+            });
+        } else if lookahead.peek(kw::id) {
+            input.parse::<kw::id>()?;
+            if attrs.id.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[error(id)] attribute"));
+            }
+            attrs.id = Some(Id { original: attr });
+        } else if lookahead.peek(kw::adhoc) {
+            input.parse::<kw::adhoc>()?;
+            if attrs.adhoc.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(adhoc)] attribute",
+                ));
+            }
+            attrs.adhoc = Some(Adhoc { original: attr });
+        } else if lookahead.peek(kw::ext) {
+            input.parse::<kw::ext>()?;
+            if attrs.ext.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(ext)] attribute",
+                ));
+            }
+            attrs.ext = Some(attr);
+        } else if lookahead.peek(kw::display_with) {
+            input.parse::<kw::display_with>()?;
+            input.parse::<Token![=]>()?;
+            let path: ExprPath = input.parse()?;
+            if attrs.display_with.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(display_with = ...)] attribute",
+                ));
+            }
+            attrs.display_with = Some(DisplayWith {
+                original: attr,
+                path,
+            });
+        } else if peek_gated(&lookahead, input, kw::diagnostic, "miette") {
+            let kw: kw::diagnostic = input.parse()?;
+            require_feature(kw.span, "diagnostic", "miette")?;
+            if attrs.diagnostic.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(diagnostic)] attribute",
+                ));
+            }
+            attrs.diagnostic = Some(attr);
+        } else if peek_gated(&lookahead, input, kw::into_response, "axum") {
+            let kw: kw::into_response = input.parse()?;
+            require_feature(kw.span, "into_response", "axum")?;
+            if attrs.into_response.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(into_response)] attribute",
+                ));
+            }
+            attrs.into_response = Some(attr);
+        } else if peek_gated(&lookahead, input, kw::label, "miette") {
+            let kw: kw::label = input.parse()?;
+            require_feature(kw.span, "label", "miette")?;
+            let text = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(display_from_lit(attr, input.parse()?))
+            } else {
+                None
+            };
+            if attrs.label.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(label)] attribute",
+                ));
+            }
+            attrs.label = Some(Label {
+                original: attr,
+                text,
+            });
+        } else if peek_gated(&lookahead, input, kw::source_code, "miette") {
+            let kw: kw::source_code = input.parse()?;
+            require_feature(kw.span, "source_code", "miette")?;
+            if attrs.source_code.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(source_code)] attribute",
+                ));
+            }
+            attrs.source_code = Some(attr);
+        } else if peek_gated(&lookahead, input, kw::related, "miette") {
+            let kw: kw::related = input.parse()?;
+            require_feature(kw.span, "related", "miette")?;
+            if attrs.related.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(related)] attribute",
+                ));
+            }
+            attrs.related = Some(attr);
         } else {
             return Err(lookahead.error());
-        };
+        }
 
-        let args = if input.is_empty() || input.peek(Token![,]) && input.peek2(End) {
-            input.parse::<Option<Token![,]>>()?;
-            TokenStream::new()
-        } else {
-            parse_token_expr(input, false)?
-        };
+        if input.is_empty() {
+            return Ok(());
+        }
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            return Ok(());
+        }
+        lookahead = input.lookahead1();
+    }
+}
 
-        let requires_fmt_machinery = !args.is_empty();
+const GRPC_CODES: &[&str] = &[
+    "Ok",
+    "Cancelled",
+    "Unknown",
+    "InvalidArgument",
+    "DeadlineExceeded",
+    "NotFound",
+    "AlreadyExists",
+    "PermissionDenied",
+    "ResourceExhausted",
+    "FailedPrecondition",
+    "Aborted",
+    "OutOfRange",
+    "Unimplemented",
+    "Internal",
+    "Unavailable",
+    "DataLoss",
+    "Unauthenticated",
+];
 
-        let display = Display {
-            original: attr,
-            fmt,
-            args,
-            requires_fmt_machinery,
-            has_bonus_display: false,
-            infinite_recursive: false,
-            implied_bounds: Set::new(),
-            bindings: Vec::new(),
-        };
-        if attrs.display.is_some() {
-            return Err(Error::new_spanned(
-                attr,
-                "only one #[error(...)] attribute is allowed",
-            ));
-        }
-        attrs.display = Some(display);
+// Exit codes from BSD sysexits.h.
+const SYSEXITS: &[(&str, u8)] = &[
+    ("EX_OK", 0),
+    ("EX_USAGE", 64),
+    ("EX_DATAERR", 65),
+    ("EX_NOINPUT", 66),
+    ("EX_NOUSER", 67),
+    ("EX_NOHOST", 68),
+    ("EX_UNAVAILABLE", 69),
+    ("EX_SOFTWARE", 70),
+    ("EX_OSERR", 71),
+    ("EX_OSFILE", 72),
+    ("EX_CANTCREAT", 73),
+    ("EX_IOERR", 74),
+    ("EX_TEMPFAIL", 75),
+    ("EX_PROTOCOL", 76),
+    ("EX_NOPERM", 77),
+    ("EX_CONFIG", 78),
+];
+
+fn feature_enabled(feature: &str) -> bool {
+    match feature {
+        "miette" => cfg!(feature = "miette"),
+        "tonic" => cfg!(feature = "tonic"),
+        "axum" => cfg!(feature = "axum"),
+        _ => unreachable!(),
+    }
+}
+
+// Keys of a disabled feature are left out of the keys listed when none match,
+// but still recognized so that require_feature can name the missing feature.
+fn peek_gated<T: Peek>(
+    lookahead: &Lookahead1,
+    input: ParseStream,
+    token: T,
+    feature: &str,
+) -> bool {
+    if feature_enabled(feature) {
+        lookahead.peek(token)
+    } else {
+        input.peek(token)
+    }
+}
+
+fn require_feature(span: Span, key: &str, feature: &str) -> Result<()> {
+    if feature_enabled(feature) {
         Ok(())
-    })
+    } else {
+        let msg = format!("#[error({key})] requires the `{feature}` feature of wherror");
+        Err(Error::new(span, msg))
+    }
+}
+
+fn display_from_lit(attr: &Attribute, fmt: LitStr) -> Display {
+    Display {
+        original: attr,
+        fmt,
+        args: TokenStream::new(),
+        requires_fmt_machinery: false,
+        has_bonus_display: false,
+        infinite_recursive: false,
+        implied_bounds: Set::new(),
+        bindings: Vec::new(),
+    }
 }
 
 fn parse_token_expr(input: ParseStream, mut begin_expr: bool) -> Result<TokenStream> {
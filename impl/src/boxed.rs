@@ -0,0 +1,198 @@
+use crate::ast::Enum;
+use crate::expand::{
+    call_site_ident, type_parameter_of_box, type_parameter_of_option, unoptional_type,
+};
+use crate::generics::InferredBounds;
+use crate::origin;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    let name = input.boxed_ident()?;
+    let repr_vis = input.vis;
+    let vis = match &input.attrs.boxed.as_ref()?.vis {
+        Visibility::Inherited => input.vis,
+        vis => vis,
+    };
+    let repr = call_site_ident(&input.ident);
+    let generics = input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let bounds = |bound: TokenStream| {
+        let mut inferred_bounds = InferredBounds::new();
+        if generics.type_params().next().is_some() {
+            inferred_bounds.insert(quote!(#repr #ty_generics), bound);
+        }
+        inferred_bounds.augment_where_clause(generics)
+    };
+    let debug_where_clause = bounds(quote!(::core::fmt::Debug));
+    let display_where_clause = bounds(quote!(::core::fmt::Display));
+    let error_where_clause = bounds(quote!(::wherror::__private::Error));
+
+    let doc = format!("Boxed `{}`, keeping `Result`s that carry it small.", repr);
+
+    let provide_method = if input.has_backtrace() {
+        Some(quote! {
+            fn provide<'_request>(&'_request self, request: &mut ::core::error::Request<'_request>) {
+                use ::wherror::__private::ThiserrorProvide as _;
+                (*self.0).thiserror_provide(request);
+            }
+        })
+    } else {
+        None
+    };
+
+    let mut from_impls = Vec::new();
+    for variant in &input.variants {
+        let from_field = match variant.from_field() {
+            Some(from_field) => from_field,
+            None => continue,
+        };
+        let track_caller = variant.location_field().map(|_| quote!(#[track_caller]));
+        let field_type = type_parameter_of_option(from_field.ty).unwrap_or(from_field.ty);
+        let mut froms = vec![unoptional_type(from_field.ty)];
+        // A Box<T> field also converts from the unboxed T.
+        if let Some(inner_type) = type_parameter_of_box(field_type) {
+            froms.push(quote!(#inner_type));
+        }
+        for from in froms {
+            from_impls.push(quote! {
+                #[allow(
+                    deprecated,
+                    unused_qualifications,
+                    clippy::elidable_lifetime_names,
+                    clippy::needless_lifetimes,
+                )]
+                #[automatically_derived]
+                impl #impl_generics ::core::convert::From<#from> for #name #ty_generics #where_clause {
+                    #track_caller
+                    fn from(source: #from) -> Self {
+                        #name(::wherror::__private::Box::new(#repr::from(source)))
+                    }
+                }
+            });
+        }
+    }
+
+    let origin_methods = if input.has_location() || input.has_source() {
+        Some(origin::impl_origin_methods(&name, generics))
+    } else {
+        None
+    };
+
+    let set_location_impl = if input.has_location() {
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics ::wherror::SetLocation for #name #ty_generics #where_clause {
+                fn set_location(&mut self, location: &'static ::core::panic::Location<'static>) {
+                    self.0.set_location(location);
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let location_method = if input.has_location() {
+        Some(quote! {
+            pub fn location(&self) -> Option<&'static ::core::panic::Location<'static>> {
+                self.0.location()
+            }
+
+            /// Replaces the location of this error, for errors constructed
+            /// without going through a generated `From` impl.
+            pub fn set_location(&mut self, location: &'static ::core::panic::Location<'static>) {
+                self.0.set_location(location);
+            }
+
+            /// Returns this error with its location replaced.
+            pub fn with_location(mut self, location: &'static ::core::panic::Location<'static>) -> Self {
+                self.0.set_location(location);
+                self
+            }
+        })
+    } else {
+        None
+    };
+
+    // The newtype needs Box, which wherror only re-exports with std.
+    let items = quote! {
+        #[doc = #doc]
+        #vis struct #name #generics (::wherror::__private::Box<#repr #ty_generics>) #where_clause;
+
+        #[allow(unused_qualifications, dead_code)]
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The unboxed error, for matching on its variants.
+            #repr_vis fn repr(&self) -> &#repr #ty_generics {
+                &self.0
+            }
+
+            /// Consumes the error, returning the unboxed error.
+            #repr_vis fn into_repr(self) -> #repr #ty_generics {
+                *self.0
+            }
+
+            #location_method
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #debug_where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&*self.0, __formatter)
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #display_where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&*self.0, __formatter)
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::Error for #name #ty_generics #error_where_clause {
+            fn source(&self) -> ::core::option::Option<&(dyn ::wherror::__private::Error + 'static)> {
+                ::wherror::__private::Error::source(&*self.0)
+            }
+            #provide_method
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#repr #ty_generics> for #name #ty_generics #where_clause {
+            fn from(repr: #repr #ty_generics) -> Self {
+                #name(::wherror::__private::Box::new(repr))
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::Located for #name #ty_generics #where_clause {
+            fn location(&self) -> ::core::option::Option<&'static ::core::panic::Location<'static>> {
+                ::wherror::__private::Located::location(&*self.0)
+            }
+
+            fn located_source(&self) -> ::core::option::Option<&dyn ::wherror::__private::Located> {
+                ::wherror::__private::Located::located_source(&*self.0)
+            }
+        }
+
+        #origin_methods
+        #set_location_impl
+
+        #(#from_impls)*
+    };
+
+    Some(quote! {
+        ::wherror::__private::require_std!("#[error(boxed)] requires the `std` feature of wherror");
+        ::wherror::__private::if_std! {
+            #items
+        }
+    })
+}
@@ -0,0 +1,118 @@
+use crate::ast::{Enum, Struct};
+use crate::attr::CaptureKind;
+use crate::expand::call_site_ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let thread_field = input.capture_field(CaptureKind::Thread);
+    let time_field = input.capture_field(CaptureKind::Time);
+    if thread_field.is_none() && time_field.is_none() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let thread_method = thread_field.map(|field| {
+        let member = &field.member;
+        thread_method(captured(CaptureKind::Thread, quote!(&self.#member)))
+    });
+    let time_method = time_field.map(|field| {
+        let member = &field.member;
+        time_method(captured(CaptureKind::Time, quote!(&self.#member)))
+    });
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #thread_method
+            #time_method
+        }
+    })
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    let has_thread = input.has_capture(CaptureKind::Thread);
+    let has_time = input.has_capture(CaptureKind::Time);
+    if !has_thread && !has_time {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let match_captured = |kind: CaptureKind| {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            match variant.capture_field(kind) {
+                Some(field) => {
+                    let member = &field.member;
+                    let captured = captured(kind, quote!(captured));
+                    quote! {
+                        #ty::#ident {#member: captured, ..} => #captured,
+                    }
+                }
+                None => quote! {
+                    #ty::#ident {..} => ::core::option::Option::None,
+                },
+            }
+        });
+        quote! {
+            #[allow(deprecated)]
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+    let thread_method = if has_thread {
+        Some(thread_method(match_captured(CaptureKind::Thread)))
+    } else {
+        None
+    };
+    let time_method = if has_time {
+        Some(time_method(match_captured(CaptureKind::Time)))
+    } else {
+        None
+    };
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #thread_method
+            #time_method
+        }
+    })
+}
+
+fn thread_method(body: TokenStream) -> TokenStream {
+    quote! {
+        /// The thread this error was created on.
+        pub fn thread(&self) -> ::core::option::Option<&::wherror::ThreadInfo> {
+            #body
+        }
+    }
+}
+
+fn time_method(body: TokenStream) -> TokenStream {
+    quote! {
+        /// The time at which this error was created.
+        pub fn timestamp(&self) -> ::core::option::Option<::std::time::SystemTime> {
+            #body
+        }
+    }
+}
+
+// The captured value of a field, given a reference to it, as an Option of a
+// reference to a ThreadInfo or of a SystemTime.
+fn captured(kind: CaptureKind, field: TokenStream) -> TokenStream {
+    match kind {
+        CaptureKind::Thread => quote! {
+            ::wherror::__private::AsCaptured::<::wherror::ThreadInfo>::as_captured(#field)
+        },
+        CaptureKind::Time => quote! {
+            ::wherror::__private::AsCaptured::<::std::time::SystemTime>::as_captured(#field)
+                .copied()
+        },
+    }
+}
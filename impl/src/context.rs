@@ -0,0 +1,61 @@
+use crate::ast::{Enum, Struct};
+use crate::expand::call_site_ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Wraps the body of a generated Display::fmt so that the alternate form
+// appends the captured context stack after the message.
+pub(crate) fn display_struct(input: &Struct, body: TokenStream) -> TokenStream {
+    let context_field = match input.context_field() {
+        Some(context_field) => context_field,
+        None => return body,
+    };
+    let member = &context_field.member;
+    display_with_context(body, context(quote!(&self.#member)))
+}
+
+pub(crate) fn display_enum(input: &Enum, body: TokenStream) -> TokenStream {
+    if !input.has_context() {
+        return body;
+    }
+    let ty = call_site_ident(&input.ident);
+    let arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        match variant.context_field() {
+            Some(field) => {
+                let member = &field.member;
+                let context = context(quote!(context));
+                quote! {
+                    #ty::#ident {#member: context, ..} => #context,
+                }
+            }
+            None => quote! {
+                #ty::#ident {..} => ::core::option::Option::None,
+            },
+        }
+    });
+    display_with_context(
+        body,
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        },
+    )
+}
+
+fn display_with_context(body: TokenStream, context: TokenStream) -> TokenStream {
+    quote! {
+        let __result: ::core::fmt::Result = { #body };
+        __result?;
+        #[allow(deprecated)]
+        let __context = #context;
+        ::wherror::__private::fmt_context(__context, __formatter)
+    }
+}
+
+fn context(field: TokenStream) -> TokenStream {
+    quote! {
+        ::wherror::__private::AsCaptured::<::wherror::ContextStack>::as_captured(#field)
+    }
+}
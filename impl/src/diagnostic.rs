@@ -0,0 +1,354 @@
+use crate::ast::{Enum, Field, Struct};
+use crate::attr::{Attrs, Display};
+use crate::expand::{call_site_ident, fields_pat, location_of, type_is_option, use_as_display};
+use crate::generics::InferredBounds;
+use crate::unraw::MemberUnraw;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::Token;
+
+// Bodies of the miette::Diagnostic methods for one struct or enum variant,
+// written in terms of the locals bound by `fields_pat`.
+struct Methods {
+    code: Option<TokenStream>,
+    severity: Option<TokenStream>,
+    help: Option<TokenStream>,
+    url: Option<TokenStream>,
+    source_code: Option<TokenStream>,
+    labels: Option<TokenStream>,
+    related: Option<TokenStream>,
+}
+
+// The impl is opt-in through #[error(diagnostic)] so that enabling the miette
+// feature anywhere in the build graph doesn't collide with Diagnostic impls
+// written by hand or derived by miette itself.
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    input.attrs.diagnostic?;
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let mut inferred_bounds = InferredBounds::new();
+    let methods = Methods::new(
+        &input.attrs,
+        &input.fields,
+        input.location_field(),
+        &mut inferred_bounds,
+    );
+
+    let pat = fields_pat(&input.fields);
+    let body = |expr: Option<TokenStream>| {
+        expr.map(|expr| {
+            quote! {
+                #[allow(unused_variables, deprecated)]
+                let Self #pat = self;
+                #expr
+            }
+        })
+    };
+
+    if input.generics.type_params().next().is_some() {
+        let self_token = <Token![Self]>::default();
+        inferred_bounds.insert(self_token, quote!(::wherror::__private::Error));
+    }
+    let where_clause = inferred_bounds.augment_where_clause(input.generics);
+    let methods = methods.into_tokens(body);
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::miette::Diagnostic for #ty #ty_generics #where_clause {
+            #methods
+        }
+    })
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    input.attrs.diagnostic?;
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let mut inferred_bounds = InferredBounds::new();
+    let variants: Vec<_> = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let methods = Methods::new(
+                &variant.attrs,
+                &variant.fields,
+                variant.location_field(),
+                &mut inferred_bounds,
+            );
+            (variant, methods)
+        })
+        .collect();
+
+    let body = |select: fn(&Methods) -> &Option<TokenStream>| {
+        if variants
+            .iter()
+            .all(|(_variant, methods)| select(methods).is_none())
+        {
+            return None;
+        }
+        let arms = variants.iter().map(|(variant, methods)| {
+            let ident = &variant.ident;
+            match select(methods) {
+                Some(expr) => {
+                    let pat = fields_pat(&variant.fields);
+                    quote!(#ty::#ident #pat => #expr,)
+                }
+                None => quote!(#ty::#ident {..} => ::core::option::Option::None,),
+            }
+        });
+        Some(quote! {
+            #[allow(unused_variables, deprecated)]
+            match self {
+                #(#arms)*
+            }
+        })
+    };
+    let methods = Methods {
+        code: body(|methods| &methods.code),
+        severity: body(|methods| &methods.severity),
+        help: body(|methods| &methods.help),
+        url: body(|methods| &methods.url),
+        source_code: body(|methods| &methods.source_code),
+        labels: body(|methods| &methods.labels),
+        related: body(|methods| &methods.related),
+    };
+
+    if input.generics.type_params().next().is_some() {
+        let self_token = <Token![Self]>::default();
+        inferred_bounds.insert(self_token, quote!(::wherror::__private::Error));
+    }
+    let where_clause = inferred_bounds.augment_where_clause(input.generics);
+    let methods = methods.into_tokens(|body| body);
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::miette::Diagnostic for #ty #ty_generics #where_clause {
+            #methods
+        }
+    })
+}
+
+impl Methods {
+    fn new(
+        attrs: &Attrs,
+        fields: &[Field],
+        location_field: Option<&Field>,
+        inferred_bounds: &mut InferredBounds,
+    ) -> Self {
+        let code = attrs.code.as_ref().map(|code| {
+            let value = &code.value;
+            quote! {
+                ::core::option::Option::Some(::std::boxed::Box::new(#value))
+            }
+        });
+
+        let severity = attrs.severity.as_ref().map(|severity| {
+            let level = &severity.level;
+            quote! {
+                ::core::option::Option::Some(::wherror::__private::miette::Severity::#level)
+            }
+        });
+
+        let mut boxed_display = |display: &Display| {
+            for &(field, bound) in &display.implied_bounds {
+                let field = &fields[field];
+                if field.contains_generic {
+                    inferred_bounds.insert(field.ty, bound);
+                }
+            }
+            let display_fn = display_fn(display);
+            quote! {
+                ::core::option::Option::Some(::std::boxed::Box::new(#display_fn))
+            }
+        };
+        let help = attrs.help.as_ref().map(&mut boxed_display);
+        let url = attrs.url.as_ref().map(&mut boxed_display);
+
+        let source_code = fields
+            .iter()
+            .find(|field| field.attrs.source_code.is_some())
+            .map(|field| {
+                let var = field_local(field);
+                if field.contains_generic {
+                    let ty = field.ty;
+                    inferred_bounds.insert(ty, quote!(::wherror::__private::miette::SourceCode));
+                }
+                if type_is_option(field.ty) {
+                    quote! {
+                        #var.as_ref().map(|source_code| {
+                            source_code as &dyn ::wherror::__private::miette::SourceCode
+                        })
+                    }
+                } else {
+                    quote! {
+                        ::core::option::Option::Some(
+                            #var as &dyn ::wherror::__private::miette::SourceCode
+                        )
+                    }
+                }
+            });
+
+        let labels: Vec<_> = fields
+            .iter()
+            .filter_map(|field| {
+                let label = field.attrs.label.as_ref()?;
+                let var = field_local(field);
+                let text = match &label.text {
+                    Some(text) => {
+                        let display_fn = display_fn(text);
+                        quote! {
+                            ::core::option::Option::Some(::std::string::ToString::to_string(&#display_fn))
+                        }
+                    }
+                    None => quote!(::core::option::Option::None),
+                };
+                Some(if type_is_option(field.ty) {
+                    quote! {
+                        ::core::clone::Clone::clone(#var).map(|span| {
+                            ::wherror::__private::miette::LabeledSpan::new_with_span(#text, span)
+                        })
+                    }
+                } else {
+                    quote! {
+                        ::core::option::Option::Some(
+                            ::wherror::__private::miette::LabeledSpan::new_with_span(
+                                #text,
+                                ::core::clone::Clone::clone(#var),
+                            )
+                        )
+                    }
+                })
+            })
+            .collect();
+        let labels = if labels.is_empty() {
+            None
+        } else {
+            Some(quote! {
+                ::core::option::Option::Some(::std::boxed::Box::new(
+                    ::core::iter::IntoIterator::into_iter([#(#labels),*]).flatten()
+                ))
+            })
+        };
+
+        let mut related = Vec::new();
+        if let Some(field) = fields.iter().find(|field| field.attrs.related.is_some()) {
+            let var = field_local(field);
+            related.push(quote! {
+                ::core::iter::IntoIterator::into_iter(#var)
+                    .map(|related| -> &dyn ::wherror::__private::miette::Diagnostic { related })
+            });
+        }
+        if let Some(field) = location_field {
+            let var = field_local(field);
+            let location = location_of(field, quote!(#var));
+            let note = quote! {
+                #location.map(|location| {
+                    ::wherror::__private::LocationNote::new(location)
+                        as &dyn ::wherror::__private::miette::Diagnostic
+                })
+            };
+            related.push(quote!(::core::iter::IntoIterator::into_iter(#note)));
+        }
+        let related = related.split_first().map(|(first, rest)| {
+            quote! {
+                ::core::option::Option::Some(::std::boxed::Box::new(
+                    #first #(.chain(#rest))*
+                ))
+            }
+        });
+
+        Methods {
+            code,
+            severity,
+            help,
+            url,
+            source_code,
+            labels,
+            related,
+        }
+    }
+
+    fn into_tokens(self, body: impl Fn(Option<TokenStream>) -> Option<TokenStream>) -> TokenStream {
+        let code = body(self.code).map(|body| {
+            quote! {
+                fn code<'_diagnostic>(&'_diagnostic self) -> ::core::option::Option<::std::boxed::Box<dyn ::core::fmt::Display + '_diagnostic>> {
+                    #body
+                }
+            }
+        });
+        let severity = body(self.severity).map(|body| {
+            quote! {
+                fn severity(&self) -> ::core::option::Option<::wherror::__private::miette::Severity> {
+                    #body
+                }
+            }
+        });
+        let help = body(self.help).map(|body| {
+            quote! {
+                fn help<'_diagnostic>(&'_diagnostic self) -> ::core::option::Option<::std::boxed::Box<dyn ::core::fmt::Display + '_diagnostic>> {
+                    #body
+                }
+            }
+        });
+        let url = body(self.url).map(|body| {
+            quote! {
+                fn url<'_diagnostic>(&'_diagnostic self) -> ::core::option::Option<::std::boxed::Box<dyn ::core::fmt::Display + '_diagnostic>> {
+                    #body
+                }
+            }
+        });
+        let source_code = body(self.source_code).map(|body| {
+            quote! {
+                fn source_code(&self) -> ::core::option::Option<&dyn ::wherror::__private::miette::SourceCode> {
+                    #body
+                }
+            }
+        });
+        let labels = body(self.labels).map(|body| {
+            quote! {
+                fn labels(&self) -> ::core::option::Option<::std::boxed::Box<dyn ::core::iter::Iterator<Item = ::wherror::__private::miette::LabeledSpan> + '_>> {
+                    #body
+                }
+            }
+        });
+        let related = body(self.related).map(|body| {
+            quote! {
+                fn related<'_diagnostic>(&'_diagnostic self) -> ::core::option::Option<::std::boxed::Box<dyn ::core::iter::Iterator<Item = &'_diagnostic dyn ::wherror::__private::miette::Diagnostic> + '_diagnostic>> {
+                    #body
+                }
+            }
+        });
+        quote! {
+            #code
+            #severity
+            #help
+            #url
+            #source_code
+            #labels
+            #related
+        }
+    }
+}
+
+// A value implementing Display by running the given format string against the
+// locals bound by `fields_pat`.
+fn display_fn(display: &Display) -> TokenStream {
+    let use_as_display = use_as_display(display.has_bonus_display);
+    quote! {
+        ::wherror::__private::DisplayFn::new(move |__formatter: &mut ::core::fmt::Formatter| -> ::core::fmt::Result {
+            #use_as_display
+            #display
+        })
+    }
+}
+
+fn field_local(field: &Field) -> Ident {
+    match &field.member {
+        MemberUnraw::Named(ident) => ident.to_local(),
+        MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::ast::{Enum, Struct};
+use crate::expand::call_site_ident;
+use crate::generics::InferredBounds;
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Generics, Token};
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let exit_code = input.attrs.exit_code.as_ref()?;
+    let ty = call_site_ident(&input.ident);
+    let code = exit_code.code.to_token_stream();
+    Some(impl_exit_code(&ty, input.generics, code))
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    if !input.has_exit_code() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let default = match &input.attrs.exit_code {
+        Some(exit_code) => exit_code.code.to_token_stream(),
+        None => quote!(1),
+    };
+
+    let arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let code = match &variant.attrs.exit_code {
+            Some(exit_code) => exit_code.code.to_token_stream(),
+            None => default.clone(),
+        };
+        quote! {
+            #ty::#ident {..} => #code,
+        }
+    });
+    let code = quote! {
+        #[allow(deprecated)]
+        match self {
+            #(#arms)*
+        }
+    };
+
+    Some(impl_exit_code(&ty, input.generics, code))
+}
+
+fn impl_exit_code(ty: &Ident, generics: &Generics, code: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut inferred_bounds = InferredBounds::new();
+    if generics.type_params().next().is_some() {
+        let self_token = <Token![Self]>::default();
+        inferred_bounds.insert(self_token, quote!(::wherror::__private::Error));
+    }
+    let termination_where_clause = inferred_bounds.augment_where_clause(generics);
+
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// Process exit code for this error.
+            pub fn exit_code(&self) -> ::wherror::__private::ExitCode {
+                ::wherror::__private::ExitCode::from(#code)
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::Termination for #ty #ty_generics #termination_where_clause {
+            fn report(self) -> ::wherror::__private::ExitCode {
+                ::wherror::__private::report(&self, #ty::exit_code(&self))
+            }
+        }
+    }
+}
@@ -1,12 +1,30 @@
+use crate::accessors;
+use crate::adhoc;
+use crate::assert;
 use crate::ast::{Enum, Field, Input, Struct};
 use crate::attr::Trait;
+use crate::boxed;
+use crate::capture;
+use crate::context;
+use crate::diagnostic;
+use crate::exit;
+use crate::ext;
+use crate::extract;
 use crate::fallback;
 use crate::generics::InferredBounds;
+use crate::grpc;
+use crate::id;
+use crate::io;
+use crate::origin;
+use crate::prop::{type_is_source_location, Captures};
+use crate::retry;
+use crate::status;
 use crate::unraw::MemberUnraw;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
-use syn::{DeriveInput, GenericArgument, PathArguments, Result, Token, Type};
+use syn::spanned::Spanned as _;
+use syn::{DeriveInput, GenericArgument, Generics, PathArguments, Result, Token, Type};
 
 pub fn derive(input: &DeriveInput) -> TokenStream {
     match try_expand(input) {
@@ -156,6 +174,7 @@ fn impl_struct(input: Struct) -> TokenStream {
             }
         }
         let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        let body = context::display_struct(&input, body);
         quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
@@ -170,27 +189,21 @@ fn impl_struct(input: Struct) -> TokenStream {
 
     let from_impl = input.from_field().map(|from_field| {
         let span = from_field.attrs.from.unwrap().span;
-        let backtrace_field = input.distinct_backtrace_field();
+        let captures = input.captures();
         let from = unoptional_type(from_field.ty);
         let track_caller = input.location_field().map(|_| quote!(#[track_caller]));
         let source_var = Ident::new("source", span);
-        let body = from_initializer(
-            from_field,
-            backtrace_field,
-            &source_var,
-            input.location_field(),
-        );
+        let body = from_initializer(from_field, quote!(#source_var), &captures);
 
         // Check if the field type (after unwrapping Option) is Box<T>
         let field_type = type_parameter_of_option(from_field.ty).unwrap_or(from_field.ty);
         let box_implementations = type_parameter_of_box(field_type).map(|inner_type| {
             // Generate From<T> implementation that boxes the value
             let inner_source_var = Ident::new("source", span);
-            let boxed_body = from_initializer_for_box(
+            let boxed_body = from_initializer(
                 from_field,
-                backtrace_field,
-                &inner_source_var,
-                input.location_field(),
+                quote!(::std::boxed::Box::new(#inner_source_var)),
+                &captures,
             );
             let inner_from_function = quote! {
                 #track_caller
@@ -210,6 +223,8 @@ fn impl_struct(input: Struct) -> TokenStream {
                     unused_qualifications,
                     clippy::elidable_lifetime_names,
                     clippy::needless_lifetimes,
+                    clippy::redundant_field_names,
+                    clippy::useless_conversion,
                 )]
                 #inner_from_impl
             }
@@ -233,6 +248,8 @@ fn impl_struct(input: Struct) -> TokenStream {
                 unused_qualifications,
                 clippy::elidable_lifetime_names,
                 clippy::needless_lifetimes,
+                clippy::redundant_field_names,
+                clippy::useless_conversion,
             )]
             #from_impl
             #box_implementations
@@ -241,15 +258,12 @@ fn impl_struct(input: Struct) -> TokenStream {
 
     let location_impl = input.location_field().map(|location_field| {
         let location = &location_field.member;
-        let body = if type_is_option(location_field.ty) {
-            quote! {
-                self.#location
-            }
-        } else {
-            quote! {
-                Some(self.#location)
-            }
+        let body = location_of(location_field, quote!(&self.#location));
+        let value = location_value(location_field, quote!(location));
+        let set_location_body = quote! {
+            self.#location = #value;
         };
+        let set_location_impl = impl_set_location(&ty, input.generics, set_location_body);
         quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
@@ -258,6 +272,7 @@ fn impl_struct(input: Struct) -> TokenStream {
                     #body
                 }
             }
+            #set_location_impl
         }
     });
 
@@ -268,6 +283,18 @@ fn impl_struct(input: Struct) -> TokenStream {
     }
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
+    let diagnostic_impl = diagnostic::impl_struct(&input);
+    let status_impl = status::impl_struct(&input);
+    let grpc_impl = grpc::impl_struct(&input);
+    let exit_impl = exit::impl_struct(&input);
+    let retry_impl = retry::impl_struct(&input);
+    let io_impl = io::impl_struct(&input);
+    let origin_impl = origin::impl_struct(&input);
+    let capture_impl = capture::impl_struct(&input);
+    let id_impl = id::impl_struct(&input);
+    let extract_impl = extract::impl_struct(&input);
+    let assert_impl = assert::impl_struct(&input);
+
     quote! {
         #[allow(unused_qualifications)]
         #[automatically_derived]
@@ -278,6 +305,17 @@ fn impl_struct(input: Struct) -> TokenStream {
         #display_impl
         #from_impl
         #location_impl
+        #diagnostic_impl
+        #status_impl
+        #grpc_impl
+        #exit_impl
+        #retry_impl
+        #io_impl
+        #origin_impl
+        #capture_impl
+        #id_impl
+        #extract_impl
+        #assert_impl
     }
 }
 
@@ -476,6 +514,15 @@ fn impl_enum(input: Enum) -> TokenStream {
                 };
                 display_implied_bounds.insert((0, Trait::Display));
                 quote!(::core::fmt::Display::fmt(#only_field, __formatter))
+            } else if let (Some(_adhoc), Some(message_field)) =
+                (&variant.attrs.adhoc, variant.message_field())
+            {
+                // #[error(adhoc)] without a display attribute: the message itself
+                let message = match &message_field.member {
+                    MemberUnraw::Named(ident) => ident.to_local(),
+                    MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+                };
+                quote!(::core::fmt::Display::fmt(#message, __formatter))
             } else if let Some(debug_attr) = &variant.attrs.debug {
                 // Variant-level #[error(debug)]: debug the variant fields directly
                 let ident = &variant.ident;
@@ -617,16 +664,22 @@ fn impl_enum(input: Enum) -> TokenStream {
         });
         let arms = arms.collect::<Vec<_>>();
         let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        let body = context::display_enum(
+            &input,
+            quote! {
+                #use_as_display
+                #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+                match #void_deref self {
+                    #(#arms,)*
+                }
+            },
+        );
         Some(quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
             impl #impl_generics ::core::fmt::Display for #ty #ty_generics #display_where_clause {
                 fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-                    #use_as_display
-                    #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
-                    match #void_deref self {
-                        #(#arms,)*
-                    }
+                    #body
                 }
             }
         })
@@ -637,12 +690,12 @@ fn impl_enum(input: Enum) -> TokenStream {
     let from_impls = input.variants.iter().flat_map(|variant| {
         let from_field = variant.from_field()?;
         let span = from_field.attrs.from.unwrap().span;
-        let backtrace_field = variant.distinct_backtrace_field();
+        let captures = variant.captures();
         let location_field = variant.location_field();
         let variant_ident = &variant.ident;
         let from = unoptional_type(from_field.ty);
         let source_var = Ident::new("source", span);
-        let body = from_initializer(from_field, backtrace_field, &source_var, location_field);
+        let body = from_initializer(from_field, quote!(#source_var), &captures);
         let track_caller = location_field.map(|_| quote!(#[track_caller]));
 
         let mut implementations = Vec::new();
@@ -666,6 +719,8 @@ fn impl_enum(input: Enum) -> TokenStream {
                 unused_qualifications,
                 clippy::elidable_lifetime_names,
                 clippy::needless_lifetimes,
+                clippy::redundant_field_names,
+                clippy::useless_conversion,
             )]
             #from_impl
         });
@@ -675,11 +730,10 @@ fn impl_enum(input: Enum) -> TokenStream {
         if let Some(inner_type) = type_parameter_of_box(field_type) {
             // Generate additional From<T> implementation that boxes the value
             let inner_source_var = Ident::new("source", span);
-            let boxed_body = from_initializer_for_box(
+            let boxed_body = from_initializer(
                 from_field,
-                backtrace_field,
-                &inner_source_var,
-                location_field,
+                quote!(::std::boxed::Box::new(#inner_source_var)),
+                &captures,
             );
             let inner_from_function = quote! {
                 #track_caller
@@ -699,6 +753,8 @@ fn impl_enum(input: Enum) -> TokenStream {
                     unused_qualifications,
                     clippy::elidable_lifetime_names,
                     clippy::needless_lifetimes,
+                    clippy::redundant_field_names,
+                    clippy::useless_conversion,
                 )]
                 #inner_from_impl
             });
@@ -713,15 +769,7 @@ fn impl_enum(input: Enum) -> TokenStream {
             if let Some(location_field) = variant.location_field() {
                 let location = &location_field.member;
                 let var_location = quote!(location);
-                let body = if type_is_option(location_field.ty) {
-                    quote! {
-                        #var_location
-                    }
-                } else {
-                    quote! {
-                        Some(#var_location)
-                    }
-                };
+                let body = location_of(location_field, var_location.clone());
                 quote! {
                     #ty::#ident {#location: #var_location, ..} => #body,
                 }
@@ -731,6 +779,27 @@ fn impl_enum(input: Enum) -> TokenStream {
                 }
             }
         });
+        let set_arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            if let Some(location_field) = variant.location_field() {
+                let location = &location_field.member;
+                let value = location_value(location_field, quote!(location));
+                quote! {
+                    #ty::#ident {#location: field, ..} => *field = #value,
+                }
+            } else {
+                quote! {
+                    #ty::#ident {..} => {}
+                }
+            }
+        });
+        let set_location_body = quote! {
+            #[allow(deprecated)]
+            match self {
+                #(#set_arms)*
+            }
+        };
+        let set_location_impl = impl_set_location(&ty, input.generics, set_location_body);
         Some(quote! {
             #[allow(unused_qualifications)]
             #[automatically_derived]
@@ -742,6 +811,7 @@ fn impl_enum(input: Enum) -> TokenStream {
                     }
                 }
             }
+            #set_location_impl
         })
     } else {
         None
@@ -754,6 +824,22 @@ fn impl_enum(input: Enum) -> TokenStream {
     }
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
+    let diagnostic_impl = diagnostic::impl_enum(&input);
+    let status_impl = status::impl_enum(&input);
+    let grpc_impl = grpc::impl_enum(&input);
+    let exit_impl = exit::impl_enum(&input);
+    let retry_impl = retry::impl_enum(&input);
+    let io_impl = io::impl_enum(&input);
+    let origin_impl = origin::impl_enum(&input);
+    let capture_impl = capture::impl_enum(&input);
+    let id_impl = id::impl_enum(&input);
+    let adhoc_impl = adhoc::impl_enum(&input);
+    let extract_impl = extract::impl_enum(&input);
+    let accessors_impl = accessors::impl_enum(&input);
+    let ext_impl = ext::impl_enum(&input);
+    let boxed_impl = boxed::impl_enum(&input);
+    let assert_impl = assert::impl_enum(&input);
+
     quote! {
         #[allow(unused_qualifications)]
         #[automatically_derived]
@@ -764,6 +850,21 @@ fn impl_enum(input: Enum) -> TokenStream {
         #display_impl
         #(#from_impls)*
         #location_impl
+        #diagnostic_impl
+        #status_impl
+        #grpc_impl
+        #exit_impl
+        #retry_impl
+        #io_impl
+        #origin_impl
+        #capture_impl
+        #id_impl
+        #adhoc_impl
+        #extract_impl
+        #accessors_impl
+        #ext_impl
+        #boxed_impl
+        #assert_impl
     }
 }
 
@@ -775,7 +876,7 @@ pub(crate) fn call_site_ident(ident: &Ident) -> Ident {
     ident
 }
 
-fn fields_pat(fields: &[Field]) -> TokenStream {
+pub(crate) fn fields_pat(fields: &[Field]) -> TokenStream {
     let mut members = fields.iter().map(|field| &field.member).peekable();
     match members.peek() {
         Some(MemberUnraw::Named(_)) => quote!({ #(#members),* }),
@@ -790,115 +891,177 @@ fn fields_pat(fields: &[Field]) -> TokenStream {
     }
 }
 
-fn use_as_display(needs_as_display: bool) -> Option<TokenStream> {
+pub(crate) fn use_as_display(needs_as_display: bool) -> Option<TokenStream> {
     if needs_as_display {
         Some(quote! {
-            use ::wherror::__private::AsDisplay as _;
+            use ::wherror::__private::{AsDisplay as _, AsDisplayOption as _};
         })
     } else {
         None
     }
 }
 
-fn from_initializer(
-    from_field: &Field,
-    backtrace_field: Option<&Field>,
-    source_var: &Ident,
-    location_field: Option<&Field>,
-) -> TokenStream {
-    let from_member = &from_field.member;
-    let some_source = if type_is_option(from_field.ty) {
-        quote!(::core::option::Option::Some(#source_var))
-    } else {
-        quote!(#source_var)
-    };
-    let backtrace = backtrace_field.map(|backtrace_field| {
-        let backtrace_member = &backtrace_field.member;
-        if type_is_option(backtrace_field.ty) {
-            quote! {
-                #backtrace_member: ::core::option::Option::Some(::wherror::__private::Backtrace::capture()),
+fn impl_set_location(ty: &Ident, generics: &Generics, body: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #[allow(unused_qualifications, clippy::useless_conversion)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// Replaces the location of this error, for errors constructed
+            /// without going through a generated `From` impl.
+            pub fn set_location(&mut self, location: &'static ::core::panic::Location<'static>) {
+                #body
             }
-        } else {
-            quote! {
-                #backtrace_member: ::core::convert::From::from(::wherror::__private::Backtrace::capture()),
+
+            /// Returns this error with its location replaced.
+            pub fn with_location(mut self, location: &'static ::core::panic::Location<'static>) -> Self {
+                #ty::set_location(&mut self, location);
+                self
             }
         }
-    });
-    let location = location_field.map(|location_field| {
-        let location_member = &location_field.member;
 
-        if type_is_option(location_field.ty) {
-            quote! {
-                #location_member: ::core::option::Option::Some(::core::panic::Location::caller()),
-            }
-        } else {
-            quote! {
-                #location_member: ::core::convert::From::from(::core::panic::Location::caller()),
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::SetLocation for #ty #ty_generics #where_clause {
+            fn set_location(&mut self, location: &'static ::core::panic::Location<'static>) {
+                #ty::set_location(self, location);
             }
         }
-    });
-    quote!({
-        #from_member: #some_source,
-        #backtrace
-        #location
-    })
+    }
 }
 
-fn from_initializer_for_box(
-    from_field: &Field,
-    backtrace_field: Option<&Field>,
-    source_var: &Ident,
-    location_field: Option<&Field>,
-) -> TokenStream {
+fn from_initializer(from_field: &Field, source: TokenStream, captures: &Captures) -> TokenStream {
     let from_member = &from_field.member;
-    // For Box<T> fields, we need to Box::new the source when receiving T
     let some_source = if type_is_option(from_field.ty) {
-        quote!(::core::option::Option::Some(::std::boxed::Box::new(#source_var)))
+        quote!(::core::option::Option::Some(#source))
     } else {
-        quote!(::std::boxed::Box::new(#source_var))
+        source
     };
-    let backtrace = backtrace_field.map(|backtrace_field| {
+    let init = init_initializers(captures);
+    let captured = captured_initializers(captures);
+    quote!({
+        #init
+        #from_member: #some_source,
+        #captured
+    })
+}
+
+// Fields with #[error(init = ...)]. Initialized ahead of the source, so that
+// their expressions may refer to it before it is moved into the error.
+pub(crate) fn init_initializers(captures: &Captures) -> TokenStream {
+    let init = captures.init.iter().map(|init_field| {
+        let init_member = &init_field.member;
+        let expr = &init_field.attrs.init.as_ref().unwrap().expr;
+        quote!(#init_member: #expr,)
+    });
+    quote!(#(#init)*)
+}
+
+// Fields captured from the environment where the error is created.
+pub(crate) fn captured_initializers(captures: &Captures) -> TokenStream {
+    let backtrace = captures.backtrace.map(|backtrace_field| {
         let backtrace_member = &backtrace_field.member;
-        if type_is_option(backtrace_field.ty) {
-            quote! {
-                #backtrace_member: ::core::option::Option::Some(::wherror::__private::Backtrace::capture()),
-            }
-        } else {
-            quote! {
-                #backtrace_member: ::core::convert::From::from(::wherror::__private::Backtrace::capture()),
-            }
-        }
+        let backtrace = captured_value(
+            backtrace_field,
+            quote!(::wherror::__private::Backtrace::capture()),
+        );
+        quote!(#backtrace_member: #backtrace,)
     });
-    let location = location_field.map(|location_field| {
+    let location = captures.location.map(|location_field| {
         let location_member = &location_field.member;
-
-        if type_is_option(location_field.ty) {
-            quote! {
-                #location_member: ::core::option::Option::Some(::core::panic::Location::caller()),
-            }
-        } else {
-            quote! {
-                #location_member: ::core::convert::From::from(::core::panic::Location::caller()),
-            }
-        }
+        let location = location_value(location_field, quote!(::core::panic::Location::caller()));
+        quote!(#location_member: #location,)
     });
-    quote!({
-        #from_member: #some_source,
+    let thread = captures.thread.map(|thread_field| {
+        let thread_member = &thread_field.member;
+        let thread = captured_value(thread_field, quote!(::wherror::ThreadInfo::current()));
+        quote!(#thread_member: #thread,)
+    });
+    let time = captures.time.map(|time_field| {
+        let time_member = &time_field.member;
+        let time = captured_value(time_field, quote!(::std::time::SystemTime::now()));
+        quote!(#time_member: #time,)
+    });
+    let context = captures.context.map(|context_field| {
+        let context_member = &context_field.member;
+        let context = captured_value(context_field, quote!(::wherror::ContextStack::capture()));
+        quote!(#context_member: #context,)
+    });
+    let id = captures.id.map(|id_field| {
+        let id_member = &id_field.member;
+        let id = captured_value(id_field, quote!(::wherror::ErrorId::new()));
+        quote!(#id_member: #id,)
+    });
+    quote! {
         #backtrace
         #location
-    })
+        #thread
+        #time
+        #context
+        #id
+    }
+}
+
+// The &'static Location<'static> held by the location field, given a
+// reference to it. Fields marked #[location] may have a type that wherror only
+// knows how to construct, whose location() is None.
+pub(crate) fn location_of(location_field: &Field, location: TokenStream) -> TokenStream {
+    if location_field.attrs.location.is_some() {
+        quote!({
+            use ::wherror::__private::{KnownLocation as _, UnknownLocation as _};
+            (&::wherror::__private::LocationOf(#location)).as_location()
+        })
+    } else {
+        quote!(::wherror::__private::AsLocation::as_location(#location))
+    }
+}
+
+// Converts `location`, a &'static Location<'static>, into the value of the
+// location field.
+fn location_value(location_field: &Field, location: TokenStream) -> TokenStream {
+    let ty = type_parameter_of_option(location_field.ty).unwrap_or(location_field.ty);
+    // A SourceLocation also records where the error type is defined, since
+    // #[track_caller] only passes on the file, line and column.
+    let location = if type_is_source_location(ty) {
+        quote! {
+            ::wherror::__private::source_location(
+                #location,
+                ::core::module_path!(),
+                ::core::option::Option::None,
+                ::core::option_env!("CARGO_PKG_NAME"),
+                ::core::option_env!("CARGO_PKG_VERSION"),
+            )
+        }
+    } else {
+        location
+    };
+
+    captured_value(location_field, location)
+}
+
+// Converts `value` into the type of a field that is filled in when the error
+// is created, wrapping it in Some for an Option field. Spanned so that a field
+// of a type that can't be built from the value is reported at the type.
+fn captured_value(field: &Field, value: TokenStream) -> TokenStream {
+    let ty = type_parameter_of_option(field.ty).unwrap_or(field.ty);
+    let from = quote_spanned!(ty.span()=> ::core::convert::From::from(#value));
+    if type_is_option(field.ty) {
+        quote!(::core::option::Option::Some(#from))
+    } else {
+        from
+    }
 }
 
-fn type_is_option(ty: &Type) -> bool {
+pub(crate) fn type_is_option(ty: &Type) -> bool {
     type_parameter_of_option(ty).is_some()
 }
 
-fn unoptional_type(ty: &Type) -> TokenStream {
+pub(crate) fn unoptional_type(ty: &Type) -> TokenStream {
     let unoptional = type_parameter_of_option(ty).unwrap_or(ty);
     quote!(#unoptional)
 }
 
-fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
+pub(crate) fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
     let path = match ty {
         Type::Path(ty) => &ty.path,
         _ => return None,
@@ -924,7 +1087,7 @@ fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
     }
 }
 
-fn type_parameter_of_box(ty: &Type) -> Option<&Type> {
+pub(crate) fn type_parameter_of_box(ty: &Type) -> Option<&Type> {
     let path = match ty {
         Type::Path(ty) => &ty.path,
         _ => return None,
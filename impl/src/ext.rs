@@ -0,0 +1,202 @@
+use crate::accessors::snake_case;
+use crate::ast::{Enum, Field, Variant};
+use crate::expand::{
+    call_site_ident, captured_initializers, init_initializers, type_is_option, unoptional_type,
+};
+use crate::prop::Captures;
+use crate::unraw::MemberUnraw;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt as _;
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    input.attrs.ext?;
+
+    let ty = call_site_ident(&input.ident);
+    let vis = input.vis;
+    let mut option_methods = Vec::new();
+    let mut result_methods = Vec::new();
+    for variant in &input.variants {
+        match variant_source(variant) {
+            Some(source_field) => result_methods.push(result_method(&ty, variant, source_field)),
+            None => option_methods.push(option_method(&ty, variant)),
+        }
+    }
+
+    let option_ext = if option_methods.is_empty() {
+        None
+    } else {
+        let option_ext = format_ident!("{}OptionExt", input.ident.unraw());
+        let option_doc = format!(
+            "Converts `None` into a variant of `{}`, capturing the caller's location.",
+            ty,
+        );
+        let (declarations, definitions): (Vec<_>, Vec<_>) = option_methods.into_iter().unzip();
+        Some(quote! {
+            #[doc = #option_doc]
+            #vis trait #option_ext<T> {
+                #(#declarations)*
+            }
+
+            #[allow(
+                deprecated,
+                unused_qualifications,
+                clippy::redundant_field_names,
+                clippy::useless_conversion,
+            )]
+            #[automatically_derived]
+            impl<T> #option_ext<T> for ::core::option::Option<T> {
+                #(#definitions)*
+            }
+        })
+    };
+
+    let result_ext = if result_methods.is_empty() {
+        None
+    } else {
+        let result_ext = format_ident!("{}ResultExt", input.ident.unraw());
+        let result_doc = format!(
+            "Wraps the error of a `Result` in a variant of `{}`, capturing the caller's location.",
+            ty,
+        );
+        let (declarations, definitions): (Vec<_>, Vec<_>) = result_methods.into_iter().unzip();
+        Some(quote! {
+            #[doc = #result_doc]
+            #vis trait #result_ext<T, E> {
+                #(#declarations)*
+            }
+
+            #[allow(
+                deprecated,
+                unused_qualifications,
+                clippy::redundant_field_names,
+                clippy::useless_conversion,
+            )]
+            #[automatically_derived]
+            impl<T, E> #result_ext<T, E> for ::core::result::Result<T, E> {
+                #(#definitions)*
+            }
+        })
+    };
+
+    Some(quote! {
+        #option_ext
+        #result_ext
+    })
+}
+
+// The field a Result's error goes into, for variants that wrap another error.
+fn variant_source<'a>(variant: &'a Variant) -> Option<&'a Field<'a>> {
+    if variant.attrs.transparent.is_some() {
+        variant.fields.first()
+    } else {
+        variant.source_field()
+    }
+}
+
+// A method and its declaration in the trait.
+fn option_method(ty: &Ident, variant: &Variant) -> (TokenStream, TokenStream) {
+    let ident = &variant.ident;
+    let method = format_ident!("or_{}", snake_case(ident), span = ident.span());
+    let doc = format!("Converts `None` into a `{}::{}`.", ty, ident);
+    let captures = variant.captures();
+    let (params, fields) = params(variant, &captures, None);
+    let init = init_initializers(&captures);
+    let captured = captured_initializers(&captures);
+    let signature = quote! {
+        #[doc = #doc]
+        #[track_caller]
+        fn #method(self, #params) -> ::core::result::Result<T, #ty>
+    };
+    let declaration = quote!(#signature;);
+    let definition = quote! {
+        #signature {
+            match self {
+                ::core::option::Option::Some(value) => ::core::result::Result::Ok(value),
+                ::core::option::Option::None => ::core::result::Result::Err(#ty::#ident {
+                    #init
+                    #fields
+                    #captured
+                }),
+            }
+        }
+    };
+    (declaration, definition)
+}
+
+fn result_method(
+    ty: &Ident,
+    variant: &Variant,
+    source_field: &Field,
+) -> (TokenStream, TokenStream) {
+    let ident = &variant.ident;
+    let method = format_ident!("or_{}", snake_case(ident), span = ident.span());
+    let doc = format!("Wraps the error in a `{}::{}`.", ty, ident);
+    let mut captures = variant.captures();
+    if captures.backtrace.map_or(false, |backtrace_field| {
+        backtrace_field.member == source_field.member
+    }) {
+        captures.backtrace = None;
+    }
+    let source_ty = unoptional_type(source_field.ty);
+    let source_member = &source_field.member;
+    let source = if type_is_option(source_field.ty) {
+        quote!(::core::option::Option::Some(source))
+    } else {
+        quote!(source)
+    };
+    let (params, fields) = params(variant, &captures, Some(source_field));
+    let init = init_initializers(&captures);
+    let captured = captured_initializers(&captures);
+    let signature = quote! {
+        #[doc = #doc]
+        #[track_caller]
+        fn #method(self, #params) -> ::core::result::Result<T, #ty>
+        where
+            E: ::core::convert::Into<#source_ty>
+    };
+    let declaration = quote!(#signature;);
+    let definition = quote! {
+        #signature {
+            match self {
+                ::core::result::Result::Ok(value) => ::core::result::Result::Ok(value),
+                ::core::result::Result::Err(source) => {
+                    let source: #source_ty = ::core::convert::Into::into(source);
+                    ::core::result::Result::Err(#ty::#ident {
+                        #init
+                        #source_member: #source,
+                        #fields
+                        #captured
+                    })
+                }
+            }
+        }
+    };
+    (declaration, definition)
+}
+
+// Parameters for the fields that are neither the source nor filled in
+// automatically, and the field initializers passing them on.
+fn params(
+    variant: &Variant,
+    captures: &Captures,
+    source_field: Option<&Field>,
+) -> (TokenStream, TokenStream) {
+    let fields = variant.fields.iter().filter(|field| {
+        !captures.contains(field)
+            && source_field.map_or(true, |source_field| source_field.member != field.member)
+    });
+    let mut params = Vec::new();
+    let mut initializers = Vec::new();
+    for field in fields {
+        let member = &field.member;
+        let var = match member {
+            MemberUnraw::Named(ident) => ident.to_local(),
+            MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+        };
+        let ty = field.ty;
+        params.push(quote!(#var: #ty,));
+        initializers.push(quote!(#member: #var,));
+    }
+    (quote!(#(#params)*), quote!(#(#initializers)*))
+}
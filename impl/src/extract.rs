@@ -0,0 +1,196 @@
+use crate::ast::{Enum, Field, Struct};
+use crate::expand::{call_site_ident, type_parameter_of_box, type_parameter_of_option};
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use std::collections::BTreeSet as Set;
+use syn::{Generics, Type};
+
+pub(crate) fn impl_struct(input: &Struct) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+
+    let try_from_impl = input.from_field().and_then(|from_field| {
+        let target = try_from_target(from_field)?;
+        let member = &from_field.member;
+        let unbox = unbox(from_field);
+        let body = if type_parameter_of_option(from_field.ty).is_some() {
+            quote! {
+                match error {
+                    #ty {#member: ::core::option::Option::Some(source), ..} => {
+                        ::core::result::Result::Ok(#unbox source)
+                    }
+                    error => ::core::result::Result::Err(error),
+                }
+            }
+        } else {
+            quote! {
+                let #ty {#member: source, ..} = error;
+                ::core::result::Result::Ok(#unbox source)
+            }
+        };
+        Some(impl_try_from(&ty, input.generics, target, body))
+    });
+
+    let source_field = if input.attrs.transparent.is_some() {
+        Some(&input.fields[0])
+    } else {
+        input.source_field()
+    };
+    let into_source_impl = source_field
+        .filter(|_| owned(input.generics))
+        .map(|source_field| {
+            let member = &source_field.member;
+            let source = take_source(source_field);
+            let body = quote! {
+                #[allow(deprecated)]
+                let #ty {#member: source, ..} = self;
+                #source
+            };
+            impl_into_source(&ty, input.generics, body)
+        });
+
+    quote! {
+        #try_from_impl
+        #into_source_impl
+    }
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+
+    // Two variants from the same type are already rejected by their
+    // conflicting From impls; don't report the TryFrom impls as well.
+    let mut targets = Set::new();
+    let try_from_impls = input.variants.iter().filter_map(|variant| {
+        let from_field = variant.from_field()?;
+        let target = try_from_target(from_field)?;
+        if !targets.insert(target.to_token_stream().to_string()) {
+            return None;
+        }
+        let ident = &variant.ident;
+        let member = &from_field.member;
+        let unbox = unbox(from_field);
+        let pat = if type_parameter_of_option(from_field.ty).is_some() {
+            quote!(::core::option::Option::Some(source))
+        } else {
+            quote!(source)
+        };
+        let body = quote! {
+            #[allow(deprecated, unreachable_patterns)]
+            match error {
+                #ty::#ident {#member: #pat, ..} => ::core::result::Result::Ok(#unbox source),
+                error => ::core::result::Result::Err(error),
+            }
+        };
+        Some(impl_try_from(&ty, input.generics, target, body))
+    });
+
+    let into_source_impl = if input.has_source() && owned(input.generics) {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let source_field = if variant.attrs.transparent.is_some() {
+                Some(&variant.fields[0])
+            } else {
+                variant.source_field()
+            };
+            match source_field {
+                Some(source_field) => {
+                    let member = &source_field.member;
+                    let source = take_source(source_field);
+                    quote! {
+                        #ty::#ident {#member: source, ..} => #source,
+                    }
+                }
+                None => quote! {
+                    #ty::#ident {..} => ::core::option::Option::None,
+                },
+            }
+        });
+        let body = quote! {
+            #[allow(deprecated)]
+            match self {
+                #(#arms)*
+            }
+        };
+        Some(impl_into_source(&ty, input.generics, body))
+    } else {
+        None
+    };
+
+    quote! {
+        #(#try_from_impls)*
+        #into_source_impl
+    }
+}
+
+fn impl_into_source(ty: &Ident, generics: &Generics, body: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        ::wherror::__private::if_std! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Consumes the error, returning its source.
+                pub fn into_source(self) -> ::core::option::Option<::wherror::__private::Box<dyn ::wherror::__private::Error + ::core::marker::Send + ::core::marker::Sync + 'static>> {
+                    use ::wherror::__private::{ErrorSource as _, NoErrorSource as _};
+                    #body
+                }
+            }
+        }
+    }
+}
+
+fn impl_try_from(ty: &Ident, generics: &Generics, target: &Type, body: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #[allow(deprecated, unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::TryFrom<#ty #ty_generics> for #target #where_clause {
+            type Error = #ty #ty_generics;
+
+            fn try_from(error: #ty #ty_generics) -> ::core::result::Result<Self, #ty #ty_generics> {
+                #body
+            }
+        }
+    }
+}
+
+// The type a #[from(try_from)] field converts back into: the field type
+// without Option, and without Box if the From impl unwraps it.
+fn try_from_target<'a>(from_field: &'a Field) -> Option<&'a Type> {
+    if !from_field.attrs.from?.try_from {
+        return None;
+    }
+    let ty = type_parameter_of_option(from_field.ty).unwrap_or(from_field.ty);
+    Some(match type_parameter_of_box(ty) {
+        Some(Type::TraitObject(_)) | None => ty,
+        Some(inner) => inner,
+    })
+}
+
+// Sources borrowed from the error can't be returned as 'static trait objects.
+fn owned(generics: &Generics) -> bool {
+    generics.lifetimes().next().is_none()
+}
+
+fn unbox(field: &Field) -> Option<TokenStream> {
+    let ty = type_parameter_of_option(field.ty).unwrap_or(field.ty);
+    match type_parameter_of_box(ty) {
+        Some(Type::TraitObject(_)) | None => None,
+        Some(_) => Some(quote!(*)),
+    }
+}
+
+// The source as a boxed trait object, if its type converts into one.
+fn take_source(field: &Field) -> TokenStream {
+    let unbox = unbox(field);
+    let take = quote! {
+        (&::wherror::__private::IntoSource::new(#unbox source)).take_source()
+    };
+    if type_parameter_of_option(field.ty).is_some() {
+        quote! {
+            source.and_then(|source| #take)
+        }
+    } else {
+        take
+    }
+}
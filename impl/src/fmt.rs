@@ -1,5 +1,6 @@
 use crate::ast::{ContainerKind, Field};
 use crate::attr::{Display, Trait};
+use crate::prop::type_is_location_ref;
 use crate::scan_expr::scan_expr;
 use crate::unraw::{IdentUnraw, MemberUnraw};
 use proc_macro2::{Delimiter, TokenStream, TokenTree};
@@ -89,8 +90,27 @@ impl Display<'_> {
                 Some(end_spec) => end_spec,
                 None => return Ok(()),
             };
+            // Location format specs like {location:short} render a location
+            // field through wherror's FormatLocation instead of its Display
+            // impl, as does a plain {location} for &'static Location fields.
+            let spec = &read[..end_spec];
+            let location_style = match spec {
+                ":file" => Some(("__file", quote!(File))),
+                ":line" => Some(("__line", quote!(Line))),
+                ":short" => Some(("__short", quote!(Short))),
+                ":full" => Some(("__full", quote!(Full))),
+                ":link" => Some(("__link", quote!(Link))),
+                "" if member_index
+                    .get(&member)
+                    .map_or(false, |&field| type_is_location_ref(fields[field].ty)) =>
+                {
+                    Some(("__location", quote!(Default)))
+                }
+                _ => None,
+            };
             let mut bonus_display = false;
-            let bound = match read[..end_spec].chars().next_back() {
+            let bound = match spec.chars().next_back() {
+                _ if location_style.is_some() => Trait::Display,
                 Some('?') => Trait::Debug,
                 Some('o') => Trait::Octal,
                 Some('x') => Trait::LowerHex,
@@ -102,7 +122,6 @@ impl Display<'_> {
                 Some(_) => Trait::Display,
                 None => {
                     bonus_display = true;
-                    has_bonus_display = true;
                     Trait::Display
                 }
             };
@@ -114,8 +133,25 @@ impl Display<'_> {
                     continue;
                 }
             };
-            implied_bounds.insert((field, bound));
-            let formatvar_prefix = if bonus_display {
+            // A {field} with #[error(display_with = ...)] is displayed through
+            // the adapter rather than the field's own Display impl.
+            let display_with = match &fields[field].attrs.display_with {
+                Some(display_with) if location_style.is_none() && bound == Trait::Display => {
+                    Some(&display_with.path)
+                }
+                _ => None,
+            };
+            has_bonus_display |= bonus_display && display_with.is_none();
+            if location_style.is_some() {
+                read = &read[end_spec..];
+            } else if display_with.is_none() {
+                implied_bounds.insert((field, bound));
+            }
+            let formatvar_prefix = if let Some((prefix, _)) = &location_style {
+                prefix
+            } else if display_with.is_some() {
+                "__display_with"
+            } else if bonus_display {
                 "__display"
             } else if bound == Trait::Pointer {
                 "__pointer"
@@ -142,7 +178,20 @@ impl Display<'_> {
                 MemberUnraw::Named(ident) => ident.to_local(),
             };
             binding_value.set_span(span.resolved_at(fields[field].member.span()));
-            let wrapped_binding_value = if bonus_display {
+            let wrapped_binding_value = if let Some((_, style)) = location_style {
+                quote! {
+                    ::wherror::__private::FormatLocation::styled(
+                        #binding_value,
+                        ::wherror::__private::LocationStyle::#style,
+                    )
+                }
+            } else if let Some(path) = display_with {
+                quote! {{
+                    #[allow(unused_imports)]
+                    use ::wherror::__private::{DisplayWithFormatter as _, DisplayWithValue as _};
+                    (&::wherror::__private::DisplayWith(#binding_value, #path)).display_with()
+                }}
+            } else if bonus_display {
                 quote_spanned!(span=> #binding_value.as_display())
             } else if bound == Trait::Pointer {
                 quote!(::wherror::__private::Var(#binding_value))
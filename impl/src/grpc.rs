@@ -0,0 +1,153 @@
+use crate::ast::{Enum, Field, Struct};
+use crate::expand::call_site_ident;
+use crate::generics::InferredBounds;
+use crate::status::{delegate, delegate_field, enum_error_code, error_code};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Generics;
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let grpc = input.attrs.grpc.as_ref()?;
+    let ty = call_site_ident(&input.ident);
+    let default = code(&grpc.code);
+
+    let grpc_code = if input.attrs.transparent.is_some() {
+        let only_field = &input.fields[0];
+        let member = &only_field.member;
+        delegate_code(only_field, quote!(&self.#member), &default)
+    } else {
+        default
+    };
+    let error_code = error_code(input.attrs.code.as_ref());
+    let location = input
+        .location_field()
+        .map(|_| quote!(#ty::location(&error)));
+
+    Some(impl_grpc_status(
+        &ty,
+        input.generics,
+        grpc_code,
+        error_code,
+        location,
+    ))
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    if !input.has_grpc() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let default = match &input.attrs.grpc {
+        Some(grpc) => code(&grpc.code),
+        None => code(&Ident::new("Unknown", ty.span())),
+    };
+
+    let code_arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        if let Some(grpc) = &variant.attrs.grpc {
+            let code = code(&grpc.code);
+            quote! {
+                #ty::#ident {..} => #code,
+            }
+        } else if let Some(field) = delegate_field(variant) {
+            let member = &field.member;
+            let code = delegate_code(field, quote!(source), &default);
+            quote! {
+                #ty::#ident {#member: source, ..} => #code,
+            }
+        } else {
+            quote! {
+                #ty::#ident {..} => #default,
+            }
+        }
+    });
+    let grpc_code = quote! {
+        #[allow(deprecated)]
+        match self {
+            #(#code_arms)*
+        }
+    };
+    let error_code = enum_error_code(input, quote!(&error));
+    let location = if input.has_location() {
+        Some(quote!(#ty::location(&error)))
+    } else {
+        None
+    };
+
+    Some(impl_grpc_status(
+        &ty,
+        input.generics,
+        grpc_code,
+        error_code,
+        location,
+    ))
+}
+
+fn impl_grpc_status(
+    ty: &Ident,
+    generics: &Generics,
+    grpc_code: TokenStream,
+    error_code: TokenStream,
+    location: Option<TokenStream>,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut inferred_bounds = InferredBounds::new();
+    if generics.type_params().next().is_some() {
+        inferred_bounds.insert(quote!(#ty #ty_generics), quote!(::core::fmt::Display));
+    }
+    let from_where_clause = inferred_bounds.augment_where_clause(generics);
+    let location = location.unwrap_or_else(|| quote!(::core::option::Option::None));
+
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// gRPC status code for this error.
+            pub fn grpc_code(&self) -> ::wherror::__private::tonic::Code {
+                #grpc_code
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::GrpcStatus for #ty #ty_generics #where_clause {
+            fn grpc_code(&self) -> ::wherror::__private::tonic::Code {
+                #ty::grpc_code(self)
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ty #ty_generics> for ::wherror::__private::tonic::Status #from_where_clause {
+            fn from(error: #ty #ty_generics) -> Self {
+                ::wherror::__private::grpc_status(
+                    #ty::grpc_code(&error),
+                    &error,
+                    #error_code,
+                    #location,
+                )
+            }
+        }
+    }
+}
+
+// Code of a transparent or #[from] source if it implements GrpcStatus,
+// otherwise the given default.
+fn delegate_code(field: &Field, source: TokenStream, default: &TokenStream) -> TokenStream {
+    let code = delegate(
+        field,
+        source,
+        default,
+        |source| quote!((&::wherror::__private::GrpcCodeOf(#source)).source_grpc_code(#default)),
+    );
+    quote!({
+        use ::wherror::__private::{DefaultGrpcCode as _, SourceGrpcCode as _};
+        #code
+    })
+}
+
+fn code(ident: &Ident) -> TokenStream {
+    quote!(::wherror::__private::tonic::Code::#ident)
+}
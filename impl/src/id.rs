@@ -0,0 +1,67 @@
+use crate::ast::{Enum, Struct};
+use crate::expand::call_site_ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let id_field = input.id_field()?;
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let member = &id_field.member;
+    let id = error_id(quote!(&self.#member));
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// The ID of this error instance, for correlating it across logs.
+            pub fn error_id(&self) -> ::core::option::Option<::wherror::ErrorId> {
+                #id
+            }
+        }
+    })
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    if !input.has_id() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        match variant.id_field() {
+            Some(field) => {
+                let member = &field.member;
+                let id = error_id(quote!(id));
+                quote! {
+                    #ty::#ident {#member: id, ..} => #id,
+                }
+            }
+            None => quote! {
+                #ty::#ident {..} => ::core::option::Option::None,
+            },
+        }
+    });
+
+    Some(quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// The ID of this error instance, for correlating it across logs.
+            pub fn error_id(&self) -> ::core::option::Option<::wherror::ErrorId> {
+                #[allow(deprecated)]
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+fn error_id(field: TokenStream) -> TokenStream {
+    quote! {
+        ::wherror::__private::AsCaptured::<::wherror::ErrorId>::as_captured(#field).copied()
+    }
+}
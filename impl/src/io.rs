@@ -0,0 +1,163 @@
+use crate::ast::{Enum, Field, Struct, Variant};
+use crate::expand::{call_site_ident, type_parameter_of_box};
+use crate::generics::InferredBounds;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Generics, Type};
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    input.attrs.into_io?;
+    let ty = call_site_ident(&input.ident);
+    let kind = match &input.attrs.io_kind {
+        Some(io_kind) => kind(&io_kind.kind),
+        None => kind(&Ident::new("Other", ty.span())),
+    };
+    let wrap = quote! {
+        ::std::io::Error::new(#ty::io_kind(&error), error)
+    };
+    Some(impl_into_io(&ty, input.generics, kind, wrap))
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    input.attrs.into_io?;
+    let ty = call_site_ident(&input.ident);
+    let default = match &input.attrs.io_kind {
+        Some(io_kind) => kind(&io_kind.kind),
+        None => kind(&Ident::new("Other", ty.span())),
+    };
+
+    let kind_arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        if let Some(io_kind) = &variant.attrs.io_kind {
+            let kind = kind(&io_kind.kind);
+            quote! {
+                #ty::#ident {..} => #kind,
+            }
+        } else if let Some(field) = io_error_field(variant) {
+            let member = &field.member;
+            quote! {
+                #ty::#ident {#member: source, ..} => source.kind(),
+            }
+        } else {
+            quote! {
+                #ty::#ident {..} => #default,
+            }
+        }
+    });
+    let kind = quote! {
+        #[allow(deprecated)]
+        match self {
+            #(#kind_arms)*
+        }
+    };
+
+    let unwrap_arms: Vec<_> = input
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            if variant.attrs.io_kind.is_some() {
+                return None;
+            }
+            let field = io_error_field(variant)?;
+            let ident = &variant.ident;
+            let member = &field.member;
+            let source = if type_parameter_of_box(field.ty).is_some() {
+                quote!(*source)
+            } else {
+                quote!(source)
+            };
+            Some(quote! {
+                #ty::#ident {#member: source, ..} => #source,
+            })
+        })
+        .collect();
+    let wrap = if unwrap_arms.is_empty() {
+        quote! {
+            ::std::io::Error::new(#ty::io_kind(&error), error)
+        }
+    } else {
+        quote! {
+            #[allow(deprecated)]
+            match error {
+                #(#unwrap_arms)*
+                error => ::std::io::Error::new(#ty::io_kind(&error), error),
+            }
+        }
+    };
+
+    Some(impl_into_io(&ty, input.generics, kind, wrap))
+}
+
+fn impl_into_io(
+    ty: &Ident,
+    generics: &Generics,
+    kind: TokenStream,
+    wrap: TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut inferred_bounds = InferredBounds::new();
+    if generics.type_params().next().is_some() {
+        inferred_bounds.insert(
+            quote!(#ty #ty_generics),
+            quote!(
+                ::wherror::__private::Error + ::core::marker::Send + ::core::marker::Sync + 'static
+            ),
+        );
+    }
+    let from_where_clause = inferred_bounds.augment_where_clause(generics);
+
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// The `std::io::ErrorKind` this error converts to.
+            pub fn io_kind(&self) -> ::std::io::ErrorKind {
+                #kind
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ty #ty_generics> for ::std::io::Error #from_where_clause {
+            fn from(error: #ty #ty_generics) -> Self {
+                #wrap
+            }
+        }
+    }
+}
+
+// A transparent or #[from] field of type io::Error or Box<io::Error>, which
+// converts back into the original io::Error.
+fn io_error_field<'a>(variant: &'a Variant) -> Option<&'a Field<'a>> {
+    let field = if variant.attrs.transparent.is_some() {
+        &variant.fields[0]
+    } else {
+        variant.from_field()?
+    };
+    let ty = type_parameter_of_box(field.ty).unwrap_or(field.ty);
+    if type_is_io_error(ty) {
+        Some(field)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn type_is_io_error(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) => &ty.path,
+        _ => return false,
+    };
+
+    let mut segments = path.segments.iter().rev();
+    match (segments.next(), segments.next()) {
+        (Some(last), Some(module)) => {
+            last.ident == "Error" && last.arguments.is_empty() && module.ident == "io"
+        }
+        _ => false,
+    }
+}
+
+fn kind(ident: &Ident) -> TokenStream {
+    quote!(::std::io::ErrorKind::#ident)
+}
@@ -19,21 +19,37 @@
 
 extern crate proc_macro;
 
+mod accessors;
+mod adhoc;
+mod assert;
 mod ast;
 mod attr;
+mod boxed;
+mod capture;
+mod context;
+mod diagnostic;
+mod exit;
 mod expand;
+mod ext;
+mod extract;
 mod fallback;
 mod fmt;
 mod generics;
+mod grpc;
+mod id;
+mod io;
+mod origin;
 mod prop;
+mod retry;
 mod scan_expr;
+mod status;
 mod unraw;
 mod valid;
 
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Error, attributes(backtrace, error, from, source))]
+#[proc_macro_derive(Error, attributes(backtrace, context, error, from, location, source))]
 pub fn derive_error(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     expand::derive(&input).into()
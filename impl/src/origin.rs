@@ -0,0 +1,152 @@
+use crate::ast::{Enum, Field, Struct};
+use crate::expand::call_site_ident;
+use crate::status::delegate;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Generics;
+
+pub(crate) fn impl_struct(input: &Struct) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+    let location = match input.location_field() {
+        Some(_) => quote!(#ty::location(self)),
+        None => quote!(::core::option::Option::None),
+    };
+    let source_field = if input.attrs.transparent.is_some() {
+        Some(&input.fields[0])
+    } else {
+        input.source_field()
+    };
+    let source = match source_field {
+        Some(source_field) => {
+            let member = &source_field.member;
+            delegate_located(source_field, quote!(&self.#member))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+    let located_impl = impl_located(&ty, input.generics, location, source);
+    let methods = if input.location_field().is_some() || source_field.is_some() {
+        Some(impl_origin_methods(&ty, input.generics))
+    } else {
+        None
+    };
+
+    quote! {
+        #located_impl
+        #methods
+    }
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+
+    let location = if input.has_location() {
+        quote!(#ty::location(self))
+    } else {
+        quote!(::core::option::Option::None)
+    };
+
+    let source = if input.has_source() {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let source_field = if variant.attrs.transparent.is_some() {
+                Some(&variant.fields[0])
+            } else {
+                variant.source_field()
+            };
+            match source_field {
+                Some(source_field) => {
+                    let member = &source_field.member;
+                    let source = delegate_located(source_field, quote!(source));
+                    quote! {
+                        #ty::#ident {#member: source, ..} => #source,
+                    }
+                }
+                None => quote! {
+                    #ty::#ident {..} => ::core::option::Option::None,
+                },
+            }
+        });
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    } else {
+        quote!(::core::option::Option::None)
+    };
+
+    let located_impl = impl_located(&ty, input.generics, location, source);
+    let methods = if input.has_location() || input.has_source() {
+        Some(impl_origin_methods(&ty, input.generics))
+    } else {
+        None
+    };
+
+    quote! {
+        #located_impl
+        #methods
+    }
+}
+
+fn impl_located(
+    ty: &Ident,
+    generics: &Generics,
+    location: TokenStream,
+    source: TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::Located for #ty #ty_generics #where_clause {
+            #[allow(deprecated)]
+            fn location(&self) -> ::core::option::Option<&'static ::core::panic::Location<'static>> {
+                #location
+            }
+
+            #[allow(deprecated)]
+            fn located_source(&self) -> ::core::option::Option<&dyn ::wherror::__private::Located> {
+                #source
+            }
+        }
+    }
+}
+
+// The inherent origin_location() and locations() methods, generated for errors
+// with a location field or a source that might lead to one.
+pub(crate) fn impl_origin_methods(ty: &Ident, generics: &Generics) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// The location of the innermost error in the source chain that
+            /// has one, which is where the failure began.
+            pub fn origin_location(&self) -> ::core::option::Option<&'static ::core::panic::Location<'static>> {
+                ::wherror::Locations::new(self).origin()
+            }
+
+            /// The type name and location of this error and of each of its
+            /// sources that has a location, outermost first.
+            pub fn locations(&self) -> ::wherror::Locations<'_> {
+                ::wherror::Locations::new(self)
+            }
+        }
+    }
+}
+
+// The source as a Located trait object if its type is a derived error,
+// otherwise None.
+fn delegate_located(field: &Field, source: TokenStream) -> TokenStream {
+    let none = quote!(::core::option::Option::None);
+    let located = delegate(
+        field,
+        source,
+        &none,
+        |source| quote!((&::wherror::__private::LocatedOf(#source)).as_located()),
+    );
+    quote!({
+        use ::wherror::__private::{DefaultLocated as _, SourceLocated as _};
+        #located
+    })
+}
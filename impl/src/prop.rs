@@ -1,10 +1,11 @@
 use crate::ast::{Enum, Field, Struct, Variant};
+use crate::attr::CaptureKind;
 use crate::unraw::MemberUnraw;
 use proc_macro2::Span;
-use syn::Type;
 use syn::{
     AngleBracketedGenericArguments, GenericArgument, Lifetime, PathArguments, TypeReference,
 };
+use syn::{Ident, Type};
 
 impl Struct<'_> {
     pub(crate) fn from_field(&self) -> Option<&Field> {
@@ -27,6 +28,37 @@ impl Struct<'_> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn context_field(&self) -> Option<&Field> {
+        context_field(&self.fields)
+    }
+
+    pub(crate) fn id_field(&self) -> Option<&Field> {
+        id_field(&self.fields)
+    }
+
+    pub(crate) fn capture_field(&self, kind: CaptureKind) -> Option<&Field> {
+        capture_field(&self.fields, kind)
+    }
+
+    pub(crate) fn captures(&self) -> Captures {
+        // A field with #[error(init = ...)] is filled in by its expression
+        // instead, whatever its type.
+        let uninit = |field: &&Field| field.attrs.init.is_none();
+        Captures {
+            backtrace: self.distinct_backtrace_field().filter(uninit),
+            location: self.location_field().filter(uninit),
+            thread: self.capture_field(CaptureKind::Thread).filter(uninit),
+            time: self.capture_field(CaptureKind::Time).filter(uninit),
+            context: self.context_field().filter(uninit),
+            id: self.id_field().filter(uninit),
+            init: self
+                .fields
+                .iter()
+                .filter(|field| field.attrs.init.is_some())
+                .collect(),
+        }
+    }
 }
 
 impl Enum<'_> {
@@ -48,6 +80,74 @@ impl Enum<'_> {
             .any(|variant| variant.location_field().is_some())
     }
 
+    pub(crate) fn has_context(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.context_field().is_some())
+    }
+
+    pub(crate) fn has_id(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.id_field().is_some())
+    }
+
+    pub(crate) fn has_capture(&self, kind: CaptureKind) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.capture_field(kind).is_some())
+    }
+
+    pub(crate) fn has_status(&self) -> bool {
+        self.attrs.status.is_some()
+            || self
+                .variants
+                .iter()
+                .any(|variant| variant.attrs.status.is_some())
+    }
+
+    pub(crate) fn has_grpc(&self) -> bool {
+        self.attrs.grpc.is_some()
+            || self
+                .variants
+                .iter()
+                .any(|variant| variant.attrs.grpc.is_some())
+    }
+
+    pub(crate) fn has_exit_code(&self) -> bool {
+        self.attrs.exit_code.is_some()
+            || self
+                .variants
+                .iter()
+                .any(|variant| variant.attrs.exit_code.is_some())
+    }
+
+    pub(crate) fn has_retryable(&self) -> bool {
+        self.attrs.retryable.is_some()
+            || self
+                .variants
+                .iter()
+                .any(|variant| variant.attrs.retryable.is_some())
+    }
+
+    // Name of the #[error(boxed)] newtype: the one given in the attribute,
+    // otherwise the enum's name without its `Repr` suffix.
+    pub(crate) fn boxed_ident(&self) -> Option<Ident> {
+        let boxed = self.attrs.boxed.as_ref()?;
+        if let Some(name) = &boxed.name {
+            return Some(name.clone());
+        }
+        let repr = self.ident.to_string();
+        let name = repr.strip_suffix("Repr").filter(|name| !name.is_empty())?;
+        Some(Ident::new(name, self.ident.span()))
+    }
+
+    pub(crate) fn adhoc_variant(&self) -> Option<&Variant> {
+        self.variants
+            .iter()
+            .find(|variant| variant.attrs.adhoc.is_some())
+    }
+
     pub(crate) fn has_display(&self) -> bool {
         self.attrs.display.is_some()
             || self.attrs.transparent.is_some()
@@ -57,6 +157,7 @@ impl Enum<'_> {
                 variant.attrs.display.is_some()
                     || variant.attrs.fmt.is_some()
                     || variant.attrs.debug.is_some()
+                    || variant.attrs.adhoc.is_some()
             })
             || self
                 .variants
@@ -86,6 +187,49 @@ impl Variant<'_> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn context_field(&self) -> Option<&Field> {
+        context_field(&self.fields)
+    }
+
+    pub(crate) fn id_field(&self) -> Option<&Field> {
+        id_field(&self.fields)
+    }
+
+    pub(crate) fn capture_field(&self, kind: CaptureKind) -> Option<&Field> {
+        capture_field(&self.fields, kind)
+    }
+
+    pub(crate) fn captures(&self) -> Captures {
+        // A field with #[error(init = ...)] is filled in by its expression
+        // instead, whatever its type.
+        let uninit = |field: &&Field| field.attrs.init.is_none();
+        Captures {
+            backtrace: self.distinct_backtrace_field().filter(uninit),
+            location: self.location_field().filter(uninit),
+            thread: self.capture_field(CaptureKind::Thread).filter(uninit),
+            time: self.capture_field(CaptureKind::Time).filter(uninit),
+            context: self.context_field().filter(uninit),
+            id: self.id_field().filter(uninit),
+            init: self
+                .fields
+                .iter()
+                .filter(|field| field.attrs.init.is_some())
+                .collect(),
+        }
+    }
+
+    // The field holding the message of an #[error(adhoc)] variant: the only
+    // one that is not filled in automatically.
+    pub(crate) fn message_field(&self) -> Option<&Field> {
+        let captures = self.captures();
+        let mut fields = self.fields.iter().filter(|field| !captures.contains(field));
+        let message_field = fields.next()?;
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(message_field)
+    }
 }
 
 impl Field<'_> {
@@ -97,6 +241,10 @@ impl Field<'_> {
         type_is_location(self.ty)
     }
 
+    pub(crate) fn is_error_id(&self) -> bool {
+        type_is_wherror(self.ty, &["ErrorId"])
+    }
+
     pub(crate) fn source_span(&self) -> Span {
         if let Some(source_attr) = &self.attrs.source {
             source_attr.span
@@ -167,6 +315,73 @@ fn location_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     None
 }
 
+pub(crate) fn context_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+    for field in fields {
+        if field.attrs.context.is_some() {
+            return Some(field);
+        }
+    }
+    None
+}
+
+pub(crate) fn id_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+    for field in fields {
+        if field.attrs.id.is_some() {
+            return Some(field);
+        }
+    }
+    for field in fields {
+        if field.is_error_id() {
+            return Some(field);
+        }
+    }
+    None
+}
+
+pub(crate) fn capture_field<'a, 'b>(
+    fields: &'a [Field<'b>],
+    kind: CaptureKind,
+) -> Option<&'a Field<'b>> {
+    for field in fields {
+        if field
+            .attrs
+            .capture
+            .map_or(false, |capture| capture.kind == kind)
+        {
+            return Some(field);
+        }
+    }
+    None
+}
+
+// Fields that generated From impls fill in next to the source.
+pub(crate) struct Captures<'a, 'b> {
+    pub backtrace: Option<&'a Field<'b>>,
+    pub location: Option<&'a Field<'b>>,
+    pub thread: Option<&'a Field<'b>>,
+    pub time: Option<&'a Field<'b>>,
+    pub context: Option<&'a Field<'b>>,
+    pub id: Option<&'a Field<'b>>,
+    pub init: Vec<&'a Field<'b>>,
+}
+
+impl Captures<'_, '_> {
+    pub(crate) fn contains(&self, field: &Field) -> bool {
+        [
+            self.backtrace,
+            self.location,
+            self.thread,
+            self.time,
+            self.context,
+            self.id,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.init.iter().copied())
+        .any(|captured| captured.member == field.member)
+    }
+}
+
 // The #[backtrace] field, if it is not the same as the #[from] field.
 fn distinct_backtrace_field<'a, 'b>(
     backtrace_field: &'a Field<'b>,
@@ -181,6 +396,11 @@ fn distinct_backtrace_field<'a, 'b>(
     }
 }
 
+pub(crate) fn type_is_source_location(ty: &Type) -> bool {
+    let names = &["SourceLocation"];
+    type_is_wherror(ty, names) || type_is_imported(ty, names)
+}
+
 fn type_is_backtrace(ty: &Type) -> bool {
     let path = match ty {
         Type::Path(ty) => &ty.path,
@@ -192,6 +412,42 @@ fn type_is_backtrace(ty: &Type) -> bool {
 }
 
 fn type_is_location(ty: &Type) -> bool {
+    let names = &["Location", "SourceLocation"];
+    type_is_wherror(ty, names) || type_is_imported(ty, names) || type_is_location_ref(ty)
+}
+
+// A bare name, such as `Location` after `use wherror::Location`, taken to be
+// wherror's own type. A longer path like `geo::Location` names some other type.
+fn type_is_imported(ty: &Type, names: &[&str]) -> bool {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty
+            .path
+            .get_ident()
+            .map_or(false, |ident| names.iter().any(|name| ident == name)),
+        _ => false,
+    }
+}
+
+// One of wherror's own types, spelled out in full as `wherror::Name`.
+fn type_is_wherror(ty: &Type, names: &[&str]) -> bool {
+    let ty = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty,
+        _ => return false,
+    };
+    let mut segments = ty.path.segments.iter();
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(krate), Some(last), None) => {
+            krate.ident == "wherror"
+                && krate.arguments.is_empty()
+                && names.iter().any(|name| last.ident == name)
+                && last.arguments.is_empty()
+        }
+        _ => false,
+    }
+}
+
+// &'static Location<'static>
+pub(crate) fn type_is_location_ref(ty: &Type) -> bool {
     let path = match ty {
         Type::Reference(TypeReference {
             lifetime: Some(Lifetime { ident: ltident, .. }),
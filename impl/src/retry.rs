@@ -0,0 +1,114 @@
+use crate::ast::{Enum, Field, Struct};
+use crate::expand::{call_site_ident, fields_pat};
+use crate::status::delegate;
+use crate::unraw::MemberUnraw;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::Generics;
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let retryable = input.attrs.retryable.as_ref()?;
+    let ty = call_site_ident(&input.ident);
+    let pat = fields_pat(&input.fields);
+    let expr = &retryable.expr;
+    let body = quote! {
+        #[allow(unused_variables, deprecated)]
+        let Self #pat = self;
+        #expr
+    };
+    Some(impl_retryable(&ty, input.generics, body))
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    if !input.has_retryable() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let default = match &input.attrs.retryable {
+        Some(retryable) => {
+            let expr = &retryable.expr;
+            quote!((#expr))
+        }
+        None => quote!(false),
+    };
+
+    let arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let source_field = if variant.attrs.transparent.is_some() {
+            Some(&variant.fields[0])
+        } else {
+            variant.source_field()
+        };
+        if let Some(retryable) = &variant.attrs.retryable {
+            let pat = fields_pat(&variant.fields);
+            let expr = &retryable.expr;
+            quote! {
+                #ty::#ident #pat => #expr,
+            }
+        } else if let Some(field) = source_field {
+            // Bind every field, since the enum's default may refer to them.
+            let pat = fields_pat(&variant.fields);
+            let source = match &field.member {
+                MemberUnraw::Named(ident) => ident.to_local(),
+                MemberUnraw::Unnamed(index) => format_ident!("_{}", index),
+            };
+            let retryable = delegate_retryable(field, quote!(#source), &default);
+            quote! {
+                #ty::#ident #pat => #retryable,
+            }
+        } else {
+            let pat = fields_pat(&variant.fields);
+            quote! {
+                #ty::#ident #pat => #default,
+            }
+        }
+    });
+    let body = quote! {
+        #[allow(unused_variables, deprecated)]
+        match self {
+            #(#arms)*
+        }
+    };
+
+    Some(impl_retryable(&ty, input.generics, body))
+}
+
+fn impl_retryable(ty: &Ident, generics: &Generics, body: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// Whether the operation that failed with this error may succeed
+            /// if retried.
+            pub fn is_retryable(&self) -> bool {
+                #body
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::Retryable for #ty #ty_generics #where_clause {
+            fn is_retryable(&self) -> bool {
+                #ty::is_retryable(self)
+            }
+        }
+    }
+}
+
+// Classification of a transparent or source field if it implements Retryable,
+// otherwise the given default.
+fn delegate_retryable(field: &Field, source: TokenStream, default: &TokenStream) -> TokenStream {
+    let retryable = delegate(
+        field,
+        source,
+        default,
+        |source| quote!((&::wherror::__private::RetryableOf(#source)).source_retryable(#default)),
+    );
+    quote!({
+        use ::wherror::__private::{DefaultRetryable as _, SourceRetryable as _};
+        #retryable
+    })
+}
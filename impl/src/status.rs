@@ -0,0 +1,225 @@
+use crate::ast::{Enum, Field, Struct, Variant};
+use crate::attr::Code;
+use crate::expand::{call_site_ident, type_parameter_of_box, type_parameter_of_option};
+use crate::generics::InferredBounds;
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Generics, Token};
+
+pub(crate) fn impl_struct(input: &Struct) -> Option<TokenStream> {
+    let status = input.attrs.status.as_ref()?;
+    let ty = call_site_ident(&input.ident);
+    let default = status.code.to_token_stream();
+
+    let http_status = if input.attrs.transparent.is_some() {
+        let only_field = &input.fields[0];
+        let member = &only_field.member;
+        delegate_status(only_field, quote!(&self.#member), &default)
+    } else {
+        default
+    };
+    let error_code = error_code(input.attrs.code.as_ref());
+
+    Some(impl_http_status(
+        &ty,
+        input.generics,
+        http_status,
+        error_code,
+        input.attrs.into_response.is_some(),
+    ))
+}
+
+pub(crate) fn impl_enum(input: &Enum) -> Option<TokenStream> {
+    if !input.has_status() {
+        return None;
+    }
+
+    let ty = call_site_ident(&input.ident);
+    let default = match &input.attrs.status {
+        Some(status) => status.code.to_token_stream(),
+        None => quote!(500),
+    };
+
+    let status_arms = input.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        if let Some(status) = &variant.attrs.status {
+            let code = &status.code;
+            quote! {
+                #ty::#ident {..} => #code,
+            }
+        } else if let Some(field) = delegate_field(variant) {
+            let member = &field.member;
+            let status = delegate_status(field, quote!(source), &default);
+            quote! {
+                #ty::#ident {#member: source, ..} => #status,
+            }
+        } else {
+            quote! {
+                #ty::#ident {..} => #default,
+            }
+        }
+    });
+    let http_status = quote! {
+        #[allow(deprecated)]
+        match self {
+            #(#status_arms)*
+        }
+    };
+
+    let error_code = enum_error_code(input, quote!(self));
+
+    Some(impl_http_status(
+        &ty,
+        input.generics,
+        http_status,
+        error_code,
+        input.attrs.into_response.is_some(),
+    ))
+}
+
+fn impl_http_status(
+    ty: &Ident,
+    generics: &Generics,
+    http_status: TokenStream,
+    error_code: TokenStream,
+    into_response: bool,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let into_response = if into_response {
+        let mut inferred_bounds = InferredBounds::new();
+        if generics.type_params().next().is_some() {
+            let self_token = <Token![Self]>::default();
+            inferred_bounds.insert(self_token, quote!(::core::fmt::Display));
+        }
+        let where_clause = inferred_bounds.augment_where_clause(generics);
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            #[automatically_derived]
+            impl #impl_generics ::wherror::__private::axum_core::response::IntoResponse for #ty #ty_generics #where_clause {
+                fn into_response(self) -> ::wherror::__private::axum_core::response::Response {
+                    ::wherror::__private::axum_core::response::IntoResponse::into_response(
+                        ::wherror::Problem::new(&self),
+                    )
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    quote! {
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            /// HTTP status code for this error.
+            pub fn http_status(&self) -> u16 {
+                #http_status
+            }
+        }
+
+        #[allow(unused_qualifications)]
+        #[automatically_derived]
+        impl #impl_generics ::wherror::__private::HttpStatus for #ty #ty_generics #where_clause {
+            fn http_status(&self) -> u16 {
+                #ty::http_status(self)
+            }
+
+            fn error_code(&self) -> ::core::option::Option<&'static str> {
+                #error_code
+            }
+        }
+
+        #into_response
+    }
+}
+
+// Transparent or #[from] field of a variant, whose status the variant reports
+// unless it has its own.
+pub(crate) fn delegate_field<'a>(variant: &'a Variant) -> Option<&'a Field<'a>> {
+    if variant.attrs.transparent.is_some() {
+        Some(&variant.fields[0])
+    } else {
+        variant
+            .from_field()
+            .filter(|field| !field.attrs.from.unwrap().no_source)
+    }
+}
+
+// Status of a transparent or #[from] source if it implements HttpStatus,
+// otherwise the given default.
+fn delegate_status(field: &Field, source: TokenStream, default: &TokenStream) -> TokenStream {
+    let status = delegate(
+        field,
+        source,
+        default,
+        |source| quote!((&::wherror::__private::StatusOf(#source)).source_status(#default)),
+    );
+    quote!({
+        use ::wherror::__private::{DefaultStatus as _, SourceStatus as _};
+        #status
+    })
+}
+
+// Applies `call` to a reference to the source, looking through Box and
+// falling back to `default` if an Option source is None.
+pub(crate) fn delegate(
+    field: &Field,
+    source: TokenStream,
+    default: &TokenStream,
+    call: impl Fn(TokenStream) -> TokenStream,
+) -> TokenStream {
+    let option = type_parameter_of_option(field.ty);
+    let deref = if type_parameter_of_box(option.unwrap_or(field.ty)).is_some() {
+        Some(quote!(&**))
+    } else {
+        None
+    };
+    if option.is_some() {
+        let call = call(quote!(#deref source));
+        quote! {
+            match #source {
+                ::core::option::Option::Some(source) => #call,
+                ::core::option::Option::None => #default,
+            }
+        }
+    } else {
+        call(quote!(#deref #source))
+    }
+}
+
+// The #[error(code = ...)] of whichever variant `value` is.
+pub(crate) fn enum_error_code(input: &Enum, value: TokenStream) -> TokenStream {
+    let ty = call_site_ident(&input.ident);
+    if input
+        .variants
+        .iter()
+        .any(|variant| variant.attrs.code.is_some())
+    {
+        let code_arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let code = error_code(variant.attrs.code.as_ref());
+            quote! {
+                #ty::#ident {..} => #code,
+            }
+        });
+        quote! {
+            #[allow(deprecated)]
+            match #value {
+                #(#code_arms)*
+            }
+        }
+    } else {
+        error_code(None)
+    }
+}
+
+pub(crate) fn error_code(code: Option<&Code>) -> TokenStream {
+    match code {
+        Some(code) => {
+            let value = &code.value;
+            quote!(::core::option::Option::Some(#value))
+        }
+        None => quote!(::core::option::Option::None),
+    }
+}
@@ -1,6 +1,9 @@
+use crate::accessors::conflicting_accessor;
 use crate::ast::{Enum, Field, Input, Struct, Variant};
-use crate::attr::Attrs;
-use syn::{Error, GenericArgument, PathArguments, Result, Type};
+use crate::attr::{Attrs, CaptureKind};
+use crate::prop::{capture_field, context_field, id_field};
+use std::iter;
+use syn::{Error, GenericArgument, Generics, PathArguments, Result, Type};
 
 impl Input<'_> {
     pub(crate) fn validate(&self) -> Result<()> {
@@ -14,6 +17,10 @@ impl Input<'_> {
 impl Struct<'_> {
     fn validate(&self) -> Result<()> {
         check_non_field_attrs(&self.attrs)?;
+        check_assertions(&self.attrs, self.generics)?;
+        if self.attrs.diagnostic.is_none() {
+            check_no_diagnostic_attrs(&self.attrs, &self.fields, "struct")?;
+        }
         if let Some(transparent) = self.attrs.transparent {
             if self.fields.len() != 1 {
                 return Err(Error::new_spanned(
@@ -34,6 +41,46 @@ impl Struct<'_> {
                 "#[error(fmt = ...)] is only supported in enums; for a struct, handwrite your own Display impl",
             ));
         }
+        if let Some(accessors) = self.attrs.accessors {
+            return Err(Error::new_spanned(
+                accessors,
+                "#[error(accessors)] is only supported in enums",
+            ));
+        }
+        if let Some(ext) = self.attrs.ext {
+            return Err(Error::new_spanned(
+                ext,
+                "#[error(ext)] is only supported in enums",
+            ));
+        }
+        if let Some(adhoc) = &self.attrs.adhoc {
+            return Err(Error::new_spanned(
+                adhoc.original,
+                "#[error(adhoc)] is only supported on enum variants",
+            ));
+        }
+        if let Some(boxed) = &self.attrs.boxed {
+            return Err(Error::new_spanned(
+                boxed.original,
+                "#[error(boxed)] is only supported in enums; for a struct, wrap it in a #[error(transparent)] newtype",
+            ));
+        }
+        if let Some(into_response) = self.attrs.into_response {
+            if self.attrs.status.is_none() {
+                return Err(Error::new_spanned(
+                    into_response,
+                    "#[error(into_response)] requires #[error(status = ...)] on the struct",
+                ));
+            }
+        }
+        if let Some(io_kind) = &self.attrs.io_kind {
+            if self.attrs.into_io.is_none() {
+                return Err(Error::new_spanned(
+                    io_kind.original,
+                    "#[error(io_kind = ...)] requires #[error(into_io)] on the struct",
+                ));
+            }
+        }
         check_field_attrs(&self.fields)?;
         for field in &self.fields {
             field.validate()?;
@@ -45,7 +92,88 @@ impl Struct<'_> {
 impl Enum<'_> {
     fn validate(&self) -> Result<()> {
         check_non_field_attrs(&self.attrs)?;
+        check_assertions(&self.attrs, self.generics)?;
+        if self.attrs.diagnostic.is_none() {
+            check_no_diagnostic_attrs(&self.attrs, &[], "enum")?;
+            for variant in &self.variants {
+                check_no_diagnostic_attrs(&variant.attrs, &variant.fields, "enum")?;
+            }
+        }
         let has_display = self.has_display();
+        if let Some(boxed) = &self.attrs.boxed {
+            if self.boxed_ident().is_none() {
+                return Err(Error::new_spanned(
+                    boxed.original,
+                    "#[error(boxed)] needs a name for the boxed error; name the enum like `MyErrorRepr` or use #[error(boxed = MyError)]",
+                ));
+            }
+        }
+        if let Some(into_response) = self.attrs.into_response {
+            if !self.has_status() {
+                return Err(Error::new_spanned(
+                    into_response,
+                    "#[error(into_response)] requires #[error(status = ...)] on the enum or one of its variants",
+                ));
+            }
+        }
+        if self.attrs.into_io.is_none() {
+            if let Some(io_kind) = iter::once(&self.attrs)
+                .chain(self.variants.iter().map(|variant| &variant.attrs))
+                .find_map(|attrs| attrs.io_kind.as_ref())
+            {
+                return Err(Error::new_spanned(
+                    io_kind.original,
+                    "#[error(io_kind = ...)] requires #[error(into_io)] on the enum",
+                ));
+            }
+        }
+        if let Some(adhoc) = &self.attrs.adhoc {
+            return Err(Error::new_spanned(
+                adhoc.original,
+                "not expected here; the #[error(adhoc)] attribute belongs on an enum variant",
+            ));
+        }
+        if self.attrs.accessors.is_some() {
+            for variant in &self.variants {
+                if let Some(name) = conflicting_accessor(variant) {
+                    return Err(Error::new_spanned(
+                        &variant.ident,
+                        format!(
+                            "#[error(accessors)] would generate `{}`, which conflicts with another method generated by the derive; rename this variant",
+                            name,
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some(ext) = self.attrs.ext {
+            if self.generics.params.iter().next().is_some() {
+                return Err(Error::new_spanned(
+                    ext,
+                    "#[error(ext)] is not supported for errors with generic parameters",
+                ));
+            }
+        }
+        if let Some(adhoc_variant) = self.adhoc_variant() {
+            for variant in &self.variants {
+                if let Some(adhoc) = &variant.attrs.adhoc {
+                    if variant.ident != adhoc_variant.ident {
+                        return Err(Error::new_spanned(
+                            adhoc.original,
+                            "duplicate #[error(adhoc)] variant",
+                        ));
+                    }
+                }
+                if let Some(from_field) = variant.from_field() {
+                    if type_is_message(from_field.ty) {
+                        return Err(Error::new_spanned(
+                            from_field.attrs.from.unwrap().original,
+                            "#[from] conflicts with the From impls of the #[error(adhoc)] variant",
+                        ));
+                    }
+                }
+            }
+        }
         for variant in &self.variants {
             variant.validate()?;
             // Only require explicit display attributes if the enum has some display capability
@@ -55,6 +183,7 @@ impl Enum<'_> {
                 && variant.attrs.transparent.is_none()
                 && variant.attrs.fmt.is_none()
                 && variant.attrs.debug.is_none()
+                && variant.attrs.adhoc.is_none()
             {
                 // Deny if the enum lacks #[error(debug)] fallback
                 if !self.attrs.debug.is_some() {
@@ -72,6 +201,54 @@ impl Enum<'_> {
 impl Variant<'_> {
     fn validate(&self) -> Result<()> {
         check_non_field_attrs(&self.attrs)?;
+        if let Some(into_io) = self.attrs.into_io {
+            return Err(Error::new_spanned(
+                into_io,
+                "not expected here; the #[error(into_io)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(accessors) = self.attrs.accessors {
+            return Err(Error::new_spanned(
+                accessors,
+                "not expected here; the #[error(accessors)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(ext) = self.attrs.ext {
+            return Err(Error::new_spanned(
+                ext,
+                "not expected here; the #[error(ext)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(diagnostic) = self.attrs.diagnostic {
+            return Err(Error::new_spanned(
+                diagnostic,
+                "not expected here; the #[error(diagnostic)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(into_response) = self.attrs.into_response {
+            return Err(Error::new_spanned(
+                into_response,
+                "not expected here; the #[error(into_response)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(boxed) = &self.attrs.boxed {
+            return Err(Error::new_spanned(
+                boxed.original,
+                "not expected here; the #[error(boxed)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(max_size) = &self.attrs.max_size {
+            return Err(Error::new_spanned(
+                max_size.original,
+                "not expected here; the #[error(max_size = ...)] attribute belongs on top of the enum",
+            ));
+        }
+        if let Some(assert) = &self.attrs.assert {
+            return Err(Error::new_spanned(
+                assert.original,
+                "not expected here; the #[error(assert(...))] attribute belongs on top of the enum",
+            ));
+        }
         if self.attrs.transparent.is_some() {
             if self.fields.len() != 1 {
                 return Err(Error::new_spanned(
@@ -90,6 +267,31 @@ impl Variant<'_> {
         for field in &self.fields {
             field.validate()?;
         }
+        if let Some(adhoc) = &self.attrs.adhoc {
+            if self.attrs.transparent.is_some() {
+                return Err(Error::new_spanned(
+                    adhoc.original,
+                    "cannot have both #[error(adhoc)] and #[error(transparent)]",
+                ));
+            }
+            let message_field = match self.message_field() {
+                Some(message_field) => message_field,
+                None => {
+                    return Err(Error::new_spanned(
+                        adhoc.original,
+                        "#[error(adhoc)] requires exactly one message field, such as String or Cow<'static, str>, besides location, backtrace and other captured fields",
+                    ));
+                }
+            };
+            if self.source_field().map_or(false, |source_field| {
+                source_field.member == message_field.member
+            }) {
+                return Err(Error::new_spanned(
+                    adhoc.original,
+                    "the message field of an #[error(adhoc)] variant can't be a source",
+                ));
+            }
+        }
         Ok(())
     }
 }
@@ -102,6 +304,42 @@ impl Field<'_> {
             Some(fmt.original)
         } else if let Some(debug) = &self.attrs.debug {
             Some(debug.original)
+        } else if let Some(code) = &self.attrs.code {
+            Some(code.original)
+        } else if let Some(help) = &self.attrs.help {
+            Some(help.original)
+        } else if let Some(url) = &self.attrs.url {
+            Some(url.original)
+        } else if let Some(severity) = &self.attrs.severity {
+            Some(severity.original)
+        } else if let Some(diagnostic) = self.attrs.diagnostic {
+            Some(diagnostic)
+        } else if let Some(status) = &self.attrs.status {
+            Some(status.original)
+        } else if let Some(into_response) = self.attrs.into_response {
+            Some(into_response)
+        } else if let Some(grpc) = &self.attrs.grpc {
+            Some(grpc.original)
+        } else if let Some(exit_code) = &self.attrs.exit_code {
+            Some(exit_code.original)
+        } else if let Some(retryable) = &self.attrs.retryable {
+            Some(retryable.original)
+        } else if let Some(io_kind) = &self.attrs.io_kind {
+            Some(io_kind.original)
+        } else if let Some(into_io) = self.attrs.into_io {
+            Some(into_io)
+        } else if let Some(accessors) = self.attrs.accessors {
+            Some(accessors)
+        } else if let Some(ext) = self.attrs.ext {
+            Some(ext)
+        } else if let Some(boxed) = &self.attrs.boxed {
+            Some(boxed.original)
+        } else if let Some(max_size) = &self.attrs.max_size {
+            Some(max_size.original)
+        } else if let Some(assert) = &self.attrs.assert {
+            Some(assert.original)
+        } else if let Some(adhoc) = &self.attrs.adhoc {
+            Some(adhoc.original)
         } else {
             None
         } {
@@ -114,6 +352,55 @@ impl Field<'_> {
     }
 }
 
+fn check_assertions(attrs: &Attrs, generics: &Generics) -> Result<()> {
+    let original = if let Some(max_size) = &attrs.max_size {
+        max_size.original
+    } else if let Some(assert) = &attrs.assert {
+        assert.original
+    } else {
+        return Ok(());
+    };
+    if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+        return Err(Error::new_spanned(
+            original,
+            "compile-time assertions are not supported for errors with type or const parameters",
+        ));
+    }
+    Ok(())
+}
+
+// The miette keys only feed the Diagnostic impl, which is generated only with
+// #[error(diagnostic)] on the struct or enum.
+fn check_no_diagnostic_attrs(attrs: &Attrs, fields: &[Field], container: &str) -> Result<()> {
+    let original = if let Some(help) = &attrs.help {
+        Some((help.original, "help = ..."))
+    } else if let Some(url) = &attrs.url {
+        Some((url.original, "url = ..."))
+    } else if let Some(severity) = &attrs.severity {
+        Some((severity.original, "severity = ..."))
+    } else {
+        fields.iter().find_map(|field| {
+            if let Some(label) = &field.attrs.label {
+                Some((label.original, "label"))
+            } else if let Some(source_code) = field.attrs.source_code {
+                Some((source_code, "source_code"))
+            } else {
+                field.attrs.related.map(|related| (related, "related"))
+            }
+        })
+    };
+    match original {
+        Some((original, key)) => Err(Error::new_spanned(
+            original,
+            format!(
+                "#[error({})] requires #[error(diagnostic)] on the {}",
+                key, container,
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
 fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
     if let Some(from) = &attrs.from {
         return Err(Error::new_spanned(
@@ -133,6 +420,60 @@ fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
             "not expected here; the #[backtrace] attribute belongs on a specific field",
         ));
     }
+    if let Some(location) = &attrs.location {
+        return Err(Error::new_spanned(
+            location,
+            "not expected here; the #[location] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(context) = &attrs.context {
+        return Err(Error::new_spanned(
+            context,
+            "not expected here; the #[context] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(init) = &attrs.init {
+        return Err(Error::new_spanned(
+            init.original,
+            "not expected here; the #[error(init = ...)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(display_with) = &attrs.display_with {
+        return Err(Error::new_spanned(
+            display_with.original,
+            "not expected here; the #[error(display_with = ...)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(id) = &attrs.id {
+        return Err(Error::new_spanned(
+            id.original,
+            "not expected here; the #[error(id)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(capture) = &attrs.capture {
+        return Err(Error::new_spanned(
+            capture.original,
+            "not expected here; the #[error(capture = ...)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(label) = &attrs.label {
+        return Err(Error::new_spanned(
+            label.original,
+            "not expected here; the #[error(label)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(source_code) = &attrs.source_code {
+        return Err(Error::new_spanned(
+            source_code,
+            "not expected here; the #[error(source_code)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(related) = &attrs.related {
+        return Err(Error::new_spanned(
+            related,
+            "not expected here; the #[error(related)] attribute belongs on a specific field",
+        ));
+    }
     if attrs.transparent.is_some() {
         if let Some(display) = &attrs.display {
             return Err(Error::new_spanned(
@@ -177,9 +518,91 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
     let mut source_field = None;
     let mut location_field: Option<&Field> = None;
     let mut backtrace_field = None;
+    let mut source_code_field = None;
+    let mut related_field = None;
+    let mut thread_field: Option<&Field> = None;
+    let mut time_field: Option<&Field> = None;
+    let mut marked_context_field: Option<&Field> = None;
+    let mut marked_id_field: Option<&Field> = None;
     let mut has_backtrace = false;
     let mut has_location = false;
     for field in fields {
+        if let Some(capture) = field.attrs.capture {
+            let (captured, kind) = match capture.kind {
+                CaptureKind::Thread => (&mut thread_field, "thread"),
+                CaptureKind::Time => (&mut time_field, "time"),
+            };
+            if captured.is_some() {
+                return Err(Error::new_spanned(
+                    capture.original,
+                    format!("duplicate #[error(capture = {})] field", kind),
+                ));
+            }
+            if field.attrs.from.is_some() || field.attrs.source.is_some() {
+                return Err(Error::new_spanned(
+                    capture.original,
+                    "#[error(capture = ...)] is not supported on the source field",
+                ));
+            }
+            *captured = Some(field);
+        }
+        if let Some(context) = field.attrs.context {
+            if marked_context_field.is_some() {
+                return Err(Error::new_spanned(
+                    context,
+                    "duplicate #[context] attribute",
+                ));
+            }
+            if field.attrs.from.is_some() || field.attrs.source.is_some() {
+                return Err(Error::new_spanned(
+                    context,
+                    "#[context] is not supported on the source field",
+                ));
+            }
+            marked_context_field = Some(field);
+        }
+        if let Some(id) = field.attrs.id {
+            if marked_id_field.is_some() {
+                return Err(Error::new_spanned(
+                    id.original,
+                    "duplicate #[error(id)] field",
+                ));
+            }
+            if field.attrs.from.is_some() || field.attrs.source.is_some() {
+                return Err(Error::new_spanned(
+                    id.original,
+                    "#[error(id)] is not supported on the source field",
+                ));
+            }
+            marked_id_field = Some(field);
+        }
+        if let Some(init) = &field.attrs.init {
+            if field.attrs.from.is_some() || field.attrs.source.is_some() {
+                return Err(Error::new_spanned(
+                    init.original,
+                    "#[error(init = ...)] is not supported on the source field",
+                ));
+            }
+            let other = if field.attrs.capture.is_some() {
+                Some("#[error(capture = ...)]")
+            } else if field.attrs.id.is_some() {
+                Some("#[error(id)]")
+            } else if field.attrs.context.is_some() {
+                Some("#[context]")
+            } else if field.attrs.location.is_some() {
+                Some("#[location]")
+            } else if field.attrs.backtrace.is_some() {
+                Some("#[backtrace]")
+            } else {
+                None
+            };
+            if let Some(other) = other {
+                return Err(Error::new_spanned(
+                    init.original,
+                    format!("cannot have both #[error(init = ...)] and {}", other),
+                ));
+            }
+        }
         if let Some(from) = field.attrs.from {
             if from_field.is_some() {
                 return Err(Error::new_spanned(
@@ -187,6 +610,12 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
                     "duplicate #[from] attribute",
                 ));
             }
+            if from.try_from && field.contains_generic {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(try_from)] is not supported for generic field types",
+                ));
+            }
             from_field = Some(field);
         }
         if let Some(source) = field.attrs.source {
@@ -215,10 +644,40 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
                     "duplicate #[location] attribute",
                 ));
             }
+            if field.attrs.from.is_some() || field.attrs.source.is_some() {
+                return Err(Error::new_spanned(
+                    location,
+                    "#[location] is not supported on the source field",
+                ));
+            }
+            if field.attrs.backtrace.is_some() {
+                return Err(Error::new_spanned(
+                    location,
+                    "#[location] is not supported on the backtrace field",
+                ));
+            }
 
             location_field = Some(field);
             has_location = true;
         }
+        if let Some(source_code) = field.attrs.source_code {
+            if source_code_field.is_some() {
+                return Err(Error::new_spanned(
+                    source_code,
+                    "duplicate #[error(source_code)] field",
+                ));
+            }
+            source_code_field = Some(field);
+        }
+        if let Some(related) = field.attrs.related {
+            if related_field.is_some() {
+                return Err(Error::new_spanned(
+                    related,
+                    "duplicate #[error(related)] field",
+                ));
+            }
+            related_field = Some(field);
+        }
         if let Some(transparent) = field.attrs.transparent {
             return Err(Error::new_spanned(
                 transparent.original,
@@ -237,13 +696,24 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
         }
     }
     if let Some(from_field) = from_field {
-        let extra_fields = has_backtrace as usize + has_location as usize;
+        let captures = [CaptureKind::Thread, CaptureKind::Time]
+            .into_iter()
+            .filter_map(|kind| capture_field(fields, kind))
+            .chain(context_field(fields))
+            .chain(id_field(fields))
+            .filter(|field| field.attrs.init.is_none())
+            .count()
+            + fields
+                .iter()
+                .filter(|field| field.attrs.init.is_some())
+                .count();
+        let extra_fields = has_backtrace as usize + has_location as usize + captures;
         let max_expected_fields = match (backtrace_field, location_field) {
             (Some(backtrace_field), Some(_)) => {
-                2 + (from_field.member != backtrace_field.member) as usize
+                2 + captures + (from_field.member != backtrace_field.member) as usize
             }
             (Some(backtrace_field), None) => {
-                1 + (from_field.member != backtrace_field.member) as usize
+                1 + captures + (from_field.member != backtrace_field.member) as usize
             }
             (None, Some(_)) => 1 + extra_fields,
             (None, None) => 1 + extra_fields,
@@ -251,7 +721,7 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
         if fields.len() > max_expected_fields {
             return Err(Error::new_spanned(
                 from_field.attrs.from.unwrap().original,
-                "deriving From requires no fields other than source, backtrace, and location",
+                "deriving From requires no fields other than the source and fields filled in automatically: backtrace, location, #[error(capture = ...)], #[context], #[error(id)] and #[error(init = ...)]; mark a location field of another type #[location]",
             ));
         }
     }
@@ -291,3 +761,19 @@ fn contains_non_static_lifetime(ty: &Type) -> bool {
         _ => false, // maybe implement later if there are common other cases
     }
 }
+
+// Types converted into the message of an #[error(adhoc)] variant by its
+// generated From impls.
+fn type_is_message(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => {
+            let last = ty.path.segments.last().unwrap();
+            last.ident == "String" && last.arguments.is_empty()
+        }
+        Type::Reference(ty) => match &*ty.elem {
+            Type::Path(elem) => elem.path.is_ident("str"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(feature = "std")]
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::string::String;
+#[cfg(feature = "std")]
+use std::thread::{self, ThreadId};
+
+/// The thread an error was created on.
+///
+/// A field of this type marked `#[error(capture = thread)]` is filled in by
+/// generated `From` impls with the current thread, and read by the generated
+/// `thread()` method.
+///
+/// ```rust
+/// # use std::io;
+/// # use std::time::SystemTime;
+/// use wherror::{Error, ThreadInfo};
+///
+/// #[derive(Error, Debug)]
+/// #[error("request failed on {thread}")]
+/// pub struct RequestError {
+///     #[from]
+///     source: io::Error,
+///     #[error(capture = thread)]
+///     thread: ThreadInfo,
+///     #[error(capture = time)]
+///     time: SystemTime,
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThreadInfo {
+    id: ThreadId,
+    name: Option<String>,
+}
+
+#[cfg(feature = "std")]
+impl ThreadInfo {
+    /// The thread that calls this function.
+    pub fn current() -> Self {
+        let thread = thread::current();
+        ThreadInfo {
+            id: thread.id(),
+            name: thread.name().map(String::from),
+        }
+    }
+
+    /// The unique identifier of the thread.
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// The name of the thread, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for ThreadInfo {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(formatter, "thread '{}'", name),
+            None => write!(formatter, "thread {:?}", self.id),
+        }
+    }
+}
+
+// Value returned by the generated thread() and timestamp() methods for a
+// capture field of type T or Option<T>.
+#[doc(hidden)]
+pub trait AsCaptured<T> {
+    fn as_captured(&self) -> Option<&T>;
+}
+
+impl<T> AsCaptured<T> for T {
+    #[inline]
+    fn as_captured(&self) -> Option<&T> {
+        Some(self)
+    }
+}
+
+impl<T> AsCaptured<T> for Option<T> {
+    #[inline]
+    fn as_captured(&self) -> Option<&T> {
+        self.as_ref()
+    }
+}
@@ -0,0 +1,150 @@
+//! Ambient context for errors, kept on a per-thread stack.
+//!
+//! Code that wants errors created beneath it to record what it was doing
+//! runs inside a [`scope`], or holds the guard returned by [`enter`]. An error
+//! with a [`ContextStack`] field marked `#[context]` takes a snapshot of the
+//! stack in its generated `From` impls, and appends it to its message when
+//! displayed with `{:#}`.
+//!
+//! ```rust
+//! # use std::io;
+//! use wherror::{context, ContextStack, Error};
+//!
+//! #[derive(Error, Debug)]
+//! #[error("failed to read manifest")]
+//! pub struct ReadError {
+//!     #[from]
+//!     source: io::Error,
+//!     #[context]
+//!     context: ContextStack,
+//! }
+//!
+//! fn read_manifest() -> Result<String, ReadError> {
+//!     Ok(std::fs::read_to_string("/nonexistent/plugin.toml")?)
+//! }
+//!
+//! let error = context::scope("loading plugin foo", || {
+//!     context::scope("parsing manifest", read_manifest)
+//! })
+//! .unwrap_err();
+//!
+//! assert_eq!(error.to_string(), "failed to read manifest");
+//! assert_eq!(
+//!     format!("{:#}", error),
+//!     "failed to read manifest, while loading plugin foo → while parsing manifest",
+//! );
+//! ```
+
+use core::cell::RefCell;
+use core::fmt::{self, Debug, Display};
+use core::marker::PhantomData;
+use std::borrow::Cow;
+use std::vec::Vec;
+
+std::thread_local! {
+    static STACK: RefCell<Vec<Cow<'static, str>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `context` pushed onto this thread's context stack.
+pub fn scope<R>(context: impl Into<Cow<'static, str>>, f: impl FnOnce() -> R) -> R {
+    let _guard = enter(context);
+    f()
+}
+
+/// Pushes `context` onto this thread's context stack until the returned
+/// guard is dropped.
+pub fn enter(context: impl Into<Cow<'static, str>>) -> ContextGuard {
+    let depth = STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.push(context.into());
+        stack.len() - 1
+    });
+    ContextGuard {
+        depth,
+        not_send: PhantomData,
+    }
+}
+
+/// Keeps a context on this thread's context stack, returned by [`enter`].
+///
+/// Dropping the guard removes its context, along with any entered after it
+/// that are still on the stack.
+#[must_use = "the context is removed as soon as the guard is dropped"]
+pub struct ContextGuard {
+    depth: usize,
+    not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        // The stack is gone if the guard outlives the thread-local storage.
+        let _ = STACK.try_with(|stack| stack.borrow_mut().truncate(self.depth));
+    }
+}
+
+impl Debug for ContextGuard {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("ContextGuard")
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+/// Snapshot of the context stack at the time an error was created.
+///
+/// Displays as `while loading plugin foo → while parsing manifest`, outermost
+/// first, and as nothing if the stack was empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContextStack {
+    contexts: Vec<Cow<'static, str>>,
+}
+
+impl ContextStack {
+    /// The contexts currently entered on this thread.
+    pub fn capture() -> Self {
+        let contexts = STACK
+            .try_with(|stack| stack.borrow().clone())
+            .unwrap_or_default();
+        ContextStack { contexts }
+    }
+
+    /// The contexts, outermost first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.contexts.iter().map(|context| &**context)
+    }
+
+    /// The number of contexts.
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Whether no context was entered.
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+}
+
+impl Display for ContextStack {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, context) in self.contexts.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str(" → ")?;
+            }
+            write!(formatter, "while {}", context)?;
+        }
+        Ok(())
+    }
+}
+
+// Appended to the message of an error with a context field when it is
+// displayed with {:#}.
+#[doc(hidden)]
+pub fn fmt_context(context: Option<&ContextStack>, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match context {
+        Some(context) if formatter.alternate() && !context.is_empty() => {
+            write!(formatter, ", {}", context)
+        }
+        _ => Ok(()),
+    }
+}
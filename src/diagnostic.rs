@@ -0,0 +1,34 @@
+use core::error::Error;
+use core::fmt::{self, Display};
+use core::panic::Location;
+use miette::{Diagnostic, Severity};
+
+// Related note reported by the miette::Diagnostic impl of errors that carry a
+// location field.
+#[doc(hidden)]
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct LocationNote(Location<'static>);
+
+impl LocationNote {
+    #[inline]
+    pub fn new(location: &'static Location<'static>) -> &'static Self {
+        // SAFETY: LocationNote is a repr(transparent) wrapper around the
+        // location.
+        unsafe { &*(location as *const Location<'static>).cast::<Self>() }
+    }
+}
+
+impl Display for LocationNote {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "error occurred at {}", self.0)
+    }
+}
+
+impl Error for LocationNote {}
+
+impl Diagnostic for LocationNote {
+    fn severity(&self) -> Option<Severity> {
+        Some(Severity::Advice)
+    }
+}
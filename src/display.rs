@@ -1,6 +1,23 @@
-use core::fmt::Display;
+#[cfg(target_has_atomic = "ptr")]
+use core::cell::UnsafeCell;
+use core::fmt::{self, Display};
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
 #[cfg(feature = "std")]
 use std::path::{self, Path, PathBuf};
+#[cfg(feature = "std")]
+use std::rc::Rc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
+use std::vec::Vec;
 
 #[doc(hidden)]
 pub trait AsDisplay<'a>: Sealed {
@@ -42,13 +59,338 @@ impl<'a> AsDisplay<'a> for PathBuf {
     }
 }
 
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for Cow<'_, Path> {
+    type Target = path::Display<'a>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.display()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for Box<Path> {
+    type Target = path::Display<'a>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.display()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for Rc<Path> {
+    type Target = path::Display<'a>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.display()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for Arc<Path> {
+    type Target = path::Display<'a>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.display()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for OsStr {
+    type Target = Cow<'a, str>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.to_string_lossy()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsDisplay<'a> for OsString {
+    type Target = Cow<'a, str>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        self.to_string_lossy()
+    }
+}
+
+impl<'a, T> AsDisplay<'a> for Option<T>
+where
+    T: Display + 'a,
+{
+    type Target = DisplayOption<&'a T>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        DisplayOption(self.as_ref())
+    }
+}
+
+// An Option of a type without a Display impl of its own, such as
+// Option<PathBuf>, displayed through the AsDisplay impl of its value. Selected
+// by autoref after the Display case above.
+#[doc(hidden)]
+pub trait AsDisplayOption<'a> {
+    type Target: Display;
+
+    fn as_display(&'a self) -> Self::Target;
+}
+
+impl<'a, T> AsDisplayOption<'a> for &Option<T>
+where
+    T: AsDisplay<'a> + 'a,
+{
+    type Target = DisplayOption<T::Target>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        DisplayOption(self.as_ref().map(T::as_display))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> AsDisplay<'a> for Vec<T>
+where
+    T: Display + 'a,
+{
+    type Target = DisplayList<'a, T>;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        DisplayList(self)
+    }
+}
+
+impl<'a> AsDisplay<'a> for Duration {
+    type Target = DisplayDuration;
+
+    #[inline]
+    fn as_display(&'a self) -> Self::Target {
+        DisplayDuration(*self)
+    }
+}
+
+// The value of an Option, or the text set by set_display_none.
+#[doc(hidden)]
+pub struct DisplayOption<T>(Option<T>);
+
+impl<T: Display> Display for DisplayOption<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Some(value) => Display::fmt(value, formatter),
+            None => formatter.pad(display_none()),
+        }
+    }
+}
+
+// The elements of a list, separated by ", ".
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub struct DisplayList<'a, T>(&'a [T]);
+
+#[cfg(feature = "std")]
+impl<T: Display> Display for DisplayList<'_, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, element) in self.0.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str(", ")?;
+            }
+            Display::fmt(element, formatter)?;
+        }
+        Ok(())
+    }
+}
+
+// A duration in its Debug form, like 1.5s or 250ms.
+#[doc(hidden)]
+pub struct DisplayDuration(Duration);
+
+impl Display for DisplayDuration {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, formatter)
+    }
+}
+
+// Set once by set_display_none: UNSET, then SETTING while the text is written,
+// then SET once it may be read.
+#[cfg(target_has_atomic = "ptr")]
+struct DisplayNone {
+    state: AtomicUsize,
+    text: UnsafeCell<&'static str>,
+}
+
+// SAFETY: `text` is only written by the one set_display_none call that moves
+// `state` from UNSET to SETTING, and only read after `state` is SET.
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl Sync for DisplayNone {}
+
+#[cfg(target_has_atomic = "ptr")]
+const UNSET: usize = 0;
+#[cfg(target_has_atomic = "ptr")]
+const SETTING: usize = 1;
+#[cfg(target_has_atomic = "ptr")]
+const SET: usize = 2;
+
+#[cfg(target_has_atomic = "ptr")]
+static DISPLAY_NONE: DisplayNone = DisplayNone {
+    state: AtomicUsize::new(UNSET),
+    text: UnsafeCell::new("none"),
+};
+
+/// Sets the text shown for an `Option` field that is `None` when it is
+/// interpolated as `{field}` in an `#[error(...)]` message. The default is
+/// `none`.
+///
+/// The text can be set once, typically at the start of `main`. Later calls
+/// return the text they were given back as an error.
+///
+/// ```rust
+/// wherror::set_display_none("-").unwrap();
+/// assert_eq!(wherror::set_display_none("n/a"), Err("n/a"));
+/// ```
+#[cfg(target_has_atomic = "ptr")]
+pub fn set_display_none(text: &'static str) -> Result<(), &'static str> {
+    match DISPLAY_NONE
+        .state
+        .compare_exchange(UNSET, SETTING, Ordering::Acquire, Ordering::Relaxed)
+    {
+        Ok(_) => {
+            // SAFETY: the state was UNSET, so no other call writes the text,
+            // and readers wait for SET.
+            unsafe { *DISPLAY_NONE.text.get() = text };
+            DISPLAY_NONE.state.store(SET, Ordering::Release);
+            Ok(())
+        }
+        Err(_) => Err(text),
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+fn display_none() -> &'static str {
+    if DISPLAY_NONE.state.load(Ordering::Acquire) == SET {
+        // SAFETY: the text is never written again once the state is SET.
+        unsafe { *DISPLAY_NONE.text.get() }
+    } else {
+        "none"
+    }
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+fn display_none() -> &'static str {
+    "none"
+}
+
+// A field with #[error(display_with = path)], displayed through `path`: either
+// a function formatting the field like Display::fmt, or one returning
+// something that implements Display. Selected by autoref.
+#[doc(hidden)]
+pub struct DisplayWith<'a, T: ?Sized, F>(pub &'a T, pub F);
+
+#[doc(hidden)]
+pub trait DisplayWithFormatter {
+    type Target: Display;
+
+    fn display_with(&self) -> Self::Target;
+}
+
+impl<'a, T: ?Sized, F> DisplayWithFormatter for DisplayWith<'a, T, F>
+where
+    F: Fn(&'a T, &mut fmt::Formatter) -> fmt::Result + Copy,
+{
+    type Target = FormatWith<'a, T, F>;
+
+    #[inline]
+    fn display_with(&self) -> Self::Target {
+        FormatWith(self.0, self.1)
+    }
+}
+
+#[doc(hidden)]
+pub trait DisplayWithValue {
+    type Target: Display;
+
+    fn display_with(&self) -> Self::Target;
+}
+
+impl<'a, T: ?Sized, F, D> DisplayWithValue for &DisplayWith<'a, T, F>
+where
+    F: Fn(&'a T) -> D,
+    D: Display,
+{
+    type Target = D;
+
+    #[inline]
+    fn display_with(&self) -> Self::Target {
+        (self.1)(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub struct FormatWith<'a, T: ?Sized, F>(&'a T, F);
+
+impl<'a, T: ?Sized, F> Display for FormatWith<'a, T, F>
+where
+    F: Fn(&'a T, &mut fmt::Formatter) -> fmt::Result,
+{
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, formatter)
+    }
+}
+
+// Display for a closure, used by generated code to hand out a `dyn Display`
+// that renders a format string against borrowed fields.
+#[doc(hidden)]
+pub struct DisplayFn<F>(F);
+
+impl<F> DisplayFn<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    #[inline]
+    pub fn new(f: F) -> Self {
+        DisplayFn(f)
+    }
+}
+
+impl<F> Display for DisplayFn<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(formatter)
+    }
+}
+
 #[doc(hidden)]
 pub trait Sealed {}
 impl<T: Display + ?Sized> Sealed for &T {}
+impl<T: Display> Sealed for Option<T> {}
+impl Sealed for Duration {}
 #[cfg(feature = "std")]
 impl Sealed for Path {}
 #[cfg(feature = "std")]
 impl Sealed for PathBuf {}
+#[cfg(feature = "std")]
+impl Sealed for Cow<'_, Path> {}
+#[cfg(feature = "std")]
+impl Sealed for Box<Path> {}
+#[cfg(feature = "std")]
+impl Sealed for Rc<Path> {}
+#[cfg(feature = "std")]
+impl Sealed for Arc<Path> {}
+#[cfg(feature = "std")]
+impl Sealed for OsStr {}
+#[cfg(feature = "std")]
+impl Sealed for OsString {}
+#[cfg(feature = "std")]
+impl<T: Display> Sealed for Vec<T> {}
 
 // Add a synthetic second impl of AsDisplay to prevent the "single applicable
 // impl" rule from making too weird inference decision based on the single impl
@@ -0,0 +1,57 @@
+use core::error::Error;
+use std::io::{self, Write as _};
+use std::process::{ExitCode, Termination};
+
+// Termination::report of errors with an #[error(exit_code = ...)] attribute:
+// prints the message and its chain of sources to stderr.
+#[doc(hidden)]
+pub fn report(error: &dyn Error, code: ExitCode) -> ExitCode {
+    let mut stderr = io::stderr().lock();
+    let _ = writeln!(stderr, "Error: {error}");
+    let mut source = error.source();
+    if source.is_some() {
+        let _ = writeln!(stderr, "\nCaused by:");
+    }
+    while let Some(cause) = source {
+        let _ = writeln!(stderr, "    {cause}");
+        source = cause.source();
+    }
+    code
+}
+
+/// Runs the body of `main`, exiting with the error's own exit code on failure.
+///
+/// The standard library's `Termination` impl for `Result<(), E>` always exits
+/// with 1, so returning `Result<(), MyError>` from `main` loses the code from
+/// `#[error(exit_code = ...)]`. Wrapping the body in `run` reports the error
+/// through its generated [`Termination`] impl instead.
+///
+/// ```
+/// # use std::process::ExitCode;
+/// # use wherror::Error;
+/// #
+/// #[derive(Error, Debug)]
+/// #[error("invalid arguments")]
+/// #[error(exit_code = 2)]
+/// pub struct UsageError;
+///
+/// fn main() -> ExitCode {
+///     wherror::run(|| {
+///         let args: Vec<String> = std::env::args().skip(1).collect();
+///         if args.len() > 3 {
+///             return Err(UsageError);
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn run<E, F>(main: F) -> ExitCode
+where
+    E: Termination,
+    F: FnOnce() -> Result<(), E>,
+{
+    match main() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => error.report(),
+    }
+}
@@ -0,0 +1,145 @@
+use core::panic;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+
+/// An error whose location can be replaced after it is constructed.
+///
+/// Implemented by `#[derive(Error)]` for errors with a location field, by
+/// forwarding to their generated `set_location()` method. For enums, variants
+/// without a location field are left unchanged.
+pub trait SetLocation {
+    /// Replaces the location of this error.
+    fn set_location(&mut self, location: &'static panic::Location<'static>);
+}
+
+#[cfg(feature = "std")]
+impl<E: SetLocation + ?Sized> SetLocation for Box<E> {
+    fn set_location(&mut self, location: &'static panic::Location<'static>) {
+        (**self).set_location(location);
+    }
+}
+
+/// Records the caller's location on errors that are not created through a
+/// generated `From` impl.
+///
+/// Locations are captured by `?` only when it converts the error. An error
+/// mapped with `map_err(MyError::Parse)` or constructed with
+/// `Err(MyError::Missing { .. })` gets its location from these methods
+/// instead.
+///
+/// ```rust
+/// # use std::panic::Location;
+/// use wherror::{Error, ResultExt as _};
+///
+/// #[derive(Error, Debug)]
+/// pub enum ConfigError {
+///     #[error("invalid port at {1}")]
+///     Port(#[source] std::num::ParseIntError, &'static Location<'static>),
+///     #[error("missing key {key} at {location}")]
+///     Missing {
+///         key: &'static str,
+///         location: &'static Location<'static>,
+///     },
+/// }
+///
+/// fn port(value: &str) -> Result<u16, ConfigError> {
+///     value.parse().or_located(ConfigError::Port)
+/// }
+///
+/// fn key(key: &'static str) -> Result<&'static str, ConfigError> {
+///     Err(ConfigError::Missing {
+///         key,
+///         location: Location::caller(),
+///     })
+///     .located()
+/// }
+/// ```
+pub trait ResultExt<T, E> {
+    /// Replaces the location of the error with the caller's.
+    #[track_caller]
+    fn located(self) -> Result<T, E>
+    where
+        E: SetLocation;
+
+    /// Maps the error with `op`, then sets the location of the new error to
+    /// the caller's.
+    #[track_caller]
+    fn map_err_located<F, O>(self, op: O) -> Result<T, F>
+    where
+        F: SetLocation,
+        O: FnOnce(E) -> F;
+
+    /// Maps the error with a constructor taking the error and a location,
+    /// such as a tuple variant whose last field is the location, passing the
+    /// caller's location.
+    #[track_caller]
+    fn or_located<F, L, C>(self, constructor: C) -> Result<T, F>
+    where
+        L: From<&'static panic::Location<'static>>,
+        C: FnOnce(E, L) -> F;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    #[track_caller]
+    fn located(self) -> Result<T, E>
+    where
+        E: SetLocation,
+    {
+        let location = panic::Location::caller();
+        self.map_err(|mut error| {
+            error.set_location(location);
+            error
+        })
+    }
+
+    #[track_caller]
+    fn map_err_located<F, O>(self, op: O) -> Result<T, F>
+    where
+        F: SetLocation,
+        O: FnOnce(E) -> F,
+    {
+        let location = panic::Location::caller();
+        self.map_err(|error| {
+            let mut error = op(error);
+            error.set_location(location);
+            error
+        })
+    }
+
+    #[track_caller]
+    fn or_located<F, L, C>(self, constructor: C) -> Result<T, F>
+    where
+        L: From<&'static panic::Location<'static>>,
+        C: FnOnce(E, L) -> F,
+    {
+        let location = panic::Location::caller();
+        self.map_err(|error| constructor(error, L::from(location)))
+    }
+}
+
+// Sets the location of an error in err!() if its type implements
+// SetLocation, and leaves it alone otherwise. Selected by autoref.
+#[doc(hidden)]
+pub struct SetLocationOf<'a, T: ?Sized>(pub &'a mut T);
+
+#[doc(hidden)]
+pub trait KnownSetLocation {
+    fn set_location(&mut self, location: &'static panic::Location<'static>);
+}
+
+impl<T: SetLocation + ?Sized> KnownSetLocation for SetLocationOf<'_, T> {
+    #[inline]
+    fn set_location(&mut self, location: &'static panic::Location<'static>) {
+        self.0.set_location(location);
+    }
+}
+
+#[doc(hidden)]
+pub trait UnknownSetLocation {
+    fn set_location(&mut self, location: &'static panic::Location<'static>);
+}
+
+impl<T: ?Sized> UnknownSetLocation for &mut SetLocationOf<'_, T> {
+    #[inline]
+    fn set_location(&mut self, _location: &'static panic::Location<'static>) {}
+}
@@ -0,0 +1,40 @@
+use core::cell::Cell;
+use core::error::Error;
+use std::boxed::Box;
+
+// Source returned by the generated into_source method: the field itself if it
+// converts into a boxed error trait object, otherwise None. Selected by
+// autoref, so that sources which can't be boxed need no bound.
+#[doc(hidden)]
+pub struct IntoSource<T>(Cell<Option<T>>);
+
+impl<T> IntoSource<T> {
+    pub fn new(source: T) -> Self {
+        IntoSource(Cell::new(Some(source)))
+    }
+}
+
+#[doc(hidden)]
+pub trait ErrorSource {
+    fn take_source(&self) -> Option<Box<dyn Error + Send + Sync + 'static>>;
+}
+
+impl<T> ErrorSource for IntoSource<T>
+where
+    T: Into<Box<dyn Error + Send + Sync + 'static>>,
+{
+    fn take_source(&self) -> Option<Box<dyn Error + Send + Sync + 'static>> {
+        self.0.take().map(Into::into)
+    }
+}
+
+#[doc(hidden)]
+pub trait NoErrorSource {
+    fn take_source(&self) -> Option<Box<dyn Error + Send + Sync + 'static>>;
+}
+
+impl<T> NoErrorSource for &IntoSource<T> {
+    fn take_source(&self) -> Option<Box<dyn Error + Send + Sync + 'static>> {
+        None
+    }
+}
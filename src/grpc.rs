@@ -0,0 +1,84 @@
+use core::fmt::Display;
+use core::panic::Location;
+use std::string::ToString;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+
+/// gRPC status code of an error.
+///
+/// Implemented by `#[derive(Error)]` for errors that carry an
+/// `#[error(grpc = ...)]` attribute on the type or on any of its variants,
+/// together with `From<E> for tonic::Status`. The resulting status has the
+/// error's `Display` message, and binary metadata entries
+/// `wherror-code-bin` and `wherror-location-bin` holding the
+/// `#[error(code = ...)]` and the location field, when those are present.
+pub trait GrpcStatus {
+    /// The gRPC status code, for example `Code::NotFound`.
+    fn grpc_code(&self) -> Code;
+}
+
+impl<T: GrpcStatus + ?Sized> GrpcStatus for &T {
+    fn grpc_code(&self) -> Code {
+        (**self).grpc_code()
+    }
+}
+
+impl GrpcStatus for Status {
+    fn grpc_code(&self) -> Code {
+        self.code()
+    }
+}
+
+const CODE_METADATA_KEY: &str = "wherror-code-bin";
+const LOCATION_METADATA_KEY: &str = "wherror-location-bin";
+
+#[doc(hidden)]
+pub fn grpc_status<E: Display + ?Sized>(
+    code: Code,
+    error: &E,
+    error_code: Option<&'static str>,
+    location: Option<&'static Location<'static>>,
+) -> Status {
+    let mut status = Status::new(code, error.to_string());
+    let metadata = status.metadata_mut();
+    if let Some(error_code) = error_code {
+        metadata.insert_bin(
+            CODE_METADATA_KEY,
+            MetadataValue::from_bytes(error_code.as_bytes()),
+        );
+    }
+    if let Some(location) = location {
+        metadata.insert_bin(
+            LOCATION_METADATA_KEY,
+            MetadataValue::from_bytes(location.to_string().as_bytes()),
+        );
+    }
+    status
+}
+
+// Code of a transparent or #[from] source: its own code if it implements
+// GrpcStatus, otherwise the default of the containing error.
+#[doc(hidden)]
+pub struct GrpcCodeOf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SourceGrpcCode {
+    fn source_grpc_code(&self, default: Code) -> Code;
+}
+
+impl<T: GrpcStatus + ?Sized> SourceGrpcCode for GrpcCodeOf<'_, T> {
+    fn source_grpc_code(&self, _default: Code) -> Code {
+        self.0.grpc_code()
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultGrpcCode {
+    fn source_grpc_code(&self, default: Code) -> Code;
+}
+
+impl<T: ?Sized> DefaultGrpcCode for &GrpcCodeOf<'_, T> {
+    fn source_grpc_code(&self, default: Code) -> Code {
+        default
+    }
+}
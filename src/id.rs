@@ -0,0 +1,106 @@
+use core::fmt::{self, Debug, Display};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT: AtomicU64 = AtomicU64::new(0);
+
+/// A process-unique identifier for an error instance, for correlating the
+/// error a client sees with the one in the logs.
+///
+/// A field of this type, spelled `wherror::ErrorId` or marked `#[error(id)]`,
+/// is filled in by generated `From` impls with a fresh ID, and read by the
+/// generated `error_id()` method. IDs count up from a starting point picked
+/// when the first one is made,
+#[cfg_attr(feature = "std", doc = "which differs between runs of the program.")]
+#[cfg_attr(
+    not(feature = "std"),
+    doc = "which is the same in every run without the `std` feature."
+)]
+///
+/// ```rust
+/// # use std::io;
+/// use wherror::{Error, ErrorId};
+///
+/// #[derive(Error, Debug)]
+/// #[error("request failed (error {id})")]
+/// pub struct RequestError {
+///     #[from]
+///     source: io::Error,
+///     #[error(id)]
+///     id: ErrorId,
+/// }
+///
+/// let error = RequestError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+/// let id = error.error_id().unwrap();
+/// assert_eq!(error.to_string(), format!("request failed (error {})", id));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorId(u64);
+
+impl ErrorId {
+    /// A new ID, distinct from every other one made by this process.
+    pub fn new() -> Self {
+        if NEXT.load(Ordering::Relaxed) == 0 {
+            // Only the first thread to get here seeds the counter.
+            let _ = NEXT.compare_exchange(0, seed(), Ordering::Relaxed, Ordering::Relaxed);
+        }
+        ErrorId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// An ID with the given value, such as one read back from a log.
+    pub const fn from_u64(id: u64) -> Self {
+        ErrorId(id)
+    }
+
+    /// The value of the ID.
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for ErrorId {
+    fn default() -> Self {
+        ErrorId::new()
+    }
+}
+
+impl From<ErrorId> for u64 {
+    fn from(id: ErrorId) -> Self {
+        id.0
+    }
+}
+
+impl Display for ErrorId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:016x}", self.0)
+    }
+}
+
+impl Debug for ErrorId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ErrorId({:016x})", self.0)
+    }
+}
+
+// Starting point for this process's IDs, mixed from the clock and process ID
+// so that IDs from different runs rarely meet. Without std there is nothing to
+// mix in, so every run starts from the same point. Never 0, which marks NEXT as
+// not yet seeded.
+fn seed() -> u64 {
+    #[cfg(feature = "std")]
+    let entropy = {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        nanos ^ (u64::from(std::process::id()) << 32)
+    };
+    #[cfg(not(feature = "std"))]
+    let entropy = 0u64;
+
+    // splitmix64 finalizer
+    let mut z = entropy.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    // Leave room to count up without wrapping around to 0.
+    (z >> 1).max(1)
+}
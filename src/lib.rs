@@ -23,6 +23,29 @@
 //! | **`#[from(no_source)] T where T: !Error + Debug + Display`** | ✅ | ❌ | wherror enhancements |
 //! | **Automatic `Box<T>` unwrapping** | ✅ | ❌ | wherror enhancements |
 //! | **`.location()` method** | ✅ | ❌ | wherror enhancements |
+//! | **Zero-sized `wherror::Location`** in release builds | ✅ | ❌ | wherror enhancements |
+//! | **`SourceLocation`** with module, function and crate, and `here!()` | ✅ | ❌ | wherror enhancements |
+//! | **Location format specs**, path hook and terminal links | ✅ | ❌ | wherror enhancements |
+//! | **`#[error(display_with = path)]`** per-field display adapters | ✅ | ❌ | wherror enhancements |
+//! | **`{var}` for `Option`, `Vec`, `Duration`, `OsStr` and shared paths** | ✅ | ❌ | wherror enhancements |
+//! | **`.origin_location()`** and `.locations()` across nested errors | ✅ | ❌ | wherror enhancements |
+//! | **`ResultExt::located()`** for errors built without `From` | ✅ | ❌ | wherror enhancements |
+//! | **`bail!`, `ensure!` and `err!`** with call-site locations | ✅ | ❌ | wherror enhancements |
+//! | **`#[error(adhoc)]`** message variants with `msg()` and `format_err!` | ✅ | ❌ | wherror enhancements |
+//! | **Thread and timestamp capture** fields | ✅ | ❌ | wherror enhancements |
+//! | **`#[error(init = expr)]`** auto-populated fields | ✅ | ❌ | wherror enhancements |
+//! | **Context scopes** captured at construction | ✅ | ❌ | wherror enhancements |
+//! | **`ErrorId`** instance IDs for log correlation | ✅ | ❌ | wherror enhancements |
+//! | **`miette::Diagnostic`** from the same derive | ✅ | ❌ | wherror enhancements |
+//! | **HTTP status** and `problem+json` responses | ✅ | ❌ | wherror enhancements |
+//! | **gRPC status** via `From<E> for tonic::Status` | ✅ | ❌ | wherror enhancements |
+//! | **Process exit codes**, `Termination` and `wherror::run()` | ✅ | ❌ | wherror enhancements |
+//! | **Retryable** error classification | ✅ | ❌ | wherror enhancements |
+//! | **`From<MyError> for io::Error`** with per-variant `ErrorKind` | ✅ | ❌ | wherror enhancements |
+//! | **Variant accessors** `is_*`, `as_*`, `into_*` | ✅ | ❌ | wherror enhancements |
+//! | **`Option`/`Result` extension traits** per error type | ✅ | ❌ | wherror enhancements |
+//! | **Boxed thin errors** with `#[error(boxed)]` | ✅ | ❌ | wherror enhancements |
+//! | **Compile-time size and auto-trait assertions** | ✅ | ❌ | wherror enhancements |
 //!
 //! Use wherror when you need these features today, with the same reliable API you know and love.
 //!
@@ -194,6 +217,79 @@
 //!   }
 //!   ```
 //!
+//!   A field whose type has no suitable `Display` impl can name an adapter with
+//!   `#[error(display_with = path)]`, which is then used wherever the field is
+//!   interpolated as `{var}`, including with a width or other Display spec.
+//!   The adapter takes a reference to the field, and either returns something
+//!   that implements `Display`, like `hex::encode`, or formats the field
+//!   itself like `Display::fmt`. Other specs such as `{var:?}` are unaffected.
+//!
+//!   ```rust
+//!   # use std::fmt;
+//!   # use wherror::Error;
+//!   #
+//!   # fn encode<T: AsRef<[u8]>>(data: T) -> String {
+//!   #     data.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect()
+//!   # }
+//!   #
+//!   # #[derive(Debug)]
+//!   # pub struct Frame(Vec<u8>);
+//!   #
+//!   fn fmt_frame(frame: &Frame, formatter: &mut fmt::Formatter) -> fmt::Result {
+//!       write!(formatter, "{}-byte frame", frame.0.len())
+//!   }
+//!
+//!   #[derive(Error, Debug)]
+//!   pub enum PacketError {
+//!       #[error("bad checksum {checksum}, raw {checksum:?}")]
+//!       Checksum {
+//!           #[error(display_with = encode)]
+//!           checksum: Vec<u8>,
+//!       },
+//!       #[error("{0} is truncated")]
+//!       Truncated(#[error(display_with = fmt_frame)] Frame),
+//!   }
+//!
+//!   let error = PacketError::Checksum { checksum: vec![0xbe, 0xef] };
+//!   assert_eq!(error.to_string(), "bad checksum beef, raw [190, 239]");
+//!   let error = PacketError::Truncated(Frame(vec![0; 3]));
+//!   assert_eq!(error.to_string(), "3-byte frame is truncated");
+//!   ```
+//!
+//!   Some common field types without a `Display` impl of their own can still
+//!   be interpolated as a plain `{var}`: `Path`, `PathBuf`, `Cow<Path>`,
+//!   `Box<Path>`, `Rc<Path>` and `Arc<Path>` display like `Path::display`,
+//!   `OsStr` and `OsString` lossily, `Duration` like its `Debug` output, and
+//!   `Vec<T>` as its elements joined by `, `. An `Option<T>` displays its
+//!   value, or `none` when absent, which can be set once at startup with
+//!   [`set_display_none`].
+//!
+//!   ```rust
+//!   # use std::path::PathBuf;
+//!   # use std::time::Duration;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("{path} locked by {owner} for {held}, waiting on {waiters}")]
+//!   pub struct LockError {
+//!       path: PathBuf,
+//!       owner: Option<u32>,
+//!       held: Duration,
+//!       waiters: Vec<String>,
+//!   }
+//!
+//!   let error = LockError {
+//!       path: PathBuf::from("db.lock"),
+//!       owner: None,
+//!       held: Duration::from_millis(1500),
+//!       waiters: vec!["backup".to_owned(), "sync".to_owned()],
+//!   };
+//!   assert_eq!(
+//!       error.to_string(),
+//!       "db.lock locked by none for 1.5s, waiting on backup, sync",
+//!   );
+//!   ```
+//!
 //! - A `From` impl is generated for each variant that contains a `#[from]`
 //!   attribute.
 //!
@@ -266,6 +362,32 @@
 //!
 //!   **Fixing compile errors**: If you see `error[E0599]: the method as_dyn_error exists for reference &T, but its trait bounds were not satisfied`, use `#[from(no_source)]` for non-Error types.
 //!
+//!   In the other direction, `#[from(try_from)]` adds a `TryFrom<MyError>`
+//!   impl for the field's type that returns the value back, or `Err(error)` if
+//!   the error is a different variant. For `Box<T>` fields this is the unboxed
+//!   `T`. It is opt-in because it conflicts with the standard library's blanket
+//!   impl whenever the type already converts from the error, as catch-all types
+//!   like `anyhow::Error` do. Errors that have a source also get an
+//!   `into_source()` method returning it as a `Box<dyn Error + Send + Sync>`,
+//!   or `None` if the source can't be boxed that way.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum MyError {
+//!       #[error("io error")]
+//!       Io(#[from(try_from)] io::Error),
+//!       #[error("other")]
+//!       Other,
+//!   }
+//!
+//!   let error = MyError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+//!   let io = io::Error::try_from(error).unwrap();
+//!   assert!(io::Error::try_from(MyError::Other).is_err());
+//!   ```
+//!
 //! - Use `#[error(debug)]` as a fallback to automatically generate Display
 //!   implementations using the Debug format. This eliminates boilerplate when your
 //!   enum variant names are already descriptive error messages.
@@ -379,6 +501,318 @@
 //!   # }
 //!   ```
 //!
+//!   To avoid paying for the location in release builds, use a
+//!   [`wherror::Location`] field instead. It is populated the same way when
+//!   debug assertions or the `track-location` feature are enabled, and is
+//!   zero-sized otherwise, in which case `.location()` returns `None` and the
+//!   field displays as `<unknown>`.
+//!
+//!   [`wherror::Location`]: crate::Location
+//!
+//!   Fields are detected by type when spelled `&'static Location<'static>`,
+//!   `Location`, `SourceLocation`, `wherror::Location` or
+//!   `wherror::SourceLocation`. A longer path such as `geo::Location` names
+//!   some other type and is left alone. Mark a field `#[location]` when its
+//!   type is spelled any other way, such as a type alias, an
+//!   `Option<&'static Location<'static>>`, or your own type implementing
+//!   `From<&'static Location<'static>>`. A marked field
+//!   takes precedence over other location fields. For types other than
+//!   wherror's location types under another name, the field is populated but
+//!   `.location()` returns `None`.
+//!
+//!   ```rust
+//!   # use std::panic::Location;
+//!   # use wherror::Error;
+//!   #
+//!   type Caller = &'static Location<'static>;
+//!
+//!   #[derive(Error, Debug)]
+//!   #[error("request failed at {caller}")]
+//!   pub struct RequestError {
+//!       #[from]
+//!       source: std::io::Error,
+//!       #[location]
+//!       caller: Caller,
+//!   }
+//!   ```
+//!
+//!   A [`wherror::SourceLocation`] field additionally records the module path,
+//!   and the crate name and version. Construct one with [`here!()`], which
+//!   also records the enclosing function, or let a `From` conversion capture
+//!   it.
+//!
+//!   Error messages may render any of these location fields with a format
+//!   spec:
+//!
+//!   | Spec | Renders |
+//!   |---|---|
+//!   | `{location}` | `src/parser.rs:42:13` |
+//!   | `{location:file}` | `src/parser.rs` |
+//!   | `{location:line}` | `42` |
+//!   | `{location:short}` | `parser.rs:42` |
+//!   | `{location:full}` | `src/parser.rs:42:13`, plus the function or module and crate of a `SourceLocation` |
+//!   | `{location:link}` | `src/parser.rs:42:13` as an OSC 8 terminal hyperlink to the file |
+//!
+//!   Paths of locations in dependencies point into the cargo registry. Use
+//!   [`set_location_path_hook`] to shorten rendered paths globally.
+//!
+//!   ```rust
+//!   # use wherror::{here, Error, SourceLocation};
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum ConfigError {
+//!       #[error("missing key {key} at {location:full}")]
+//!       MissingKey {
+//!           key: &'static str,
+//!           location: SourceLocation,
+//!       },
+//!   }
+//!
+//!   let error = ConfigError::MissingKey {
+//!       key: "port",
+//!       location: here!(),
+//!   };
+//!   ```
+//!
+//!   [`wherror::SourceLocation`]: crate::SourceLocation
+//!   [`here!()`]: crate::here
+//!   [`set_location_path_hook`]: crate::set_location_path_hook
+//!
+//!   When errors wrap one another, each layer records where it was converted.
+//!   Errors with a location field or a source get `.origin_location()`, the
+//!   location of the innermost source that has one, following `#[source]`,
+//!   `#[from]` and transparent fields whose types are also derived with
+//!   wherror. A wrapper without a location of its own, such as an enum of
+//!   transparent variants, reports the locations of its sources. `.locations()` iterates over the type name and
+//!   location of every such layer, outermost first.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::panic::Location;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("read failed")]
+//!   pub struct ReadError {
+//!       #[from]
+//!       source: io::Error,
+//!       location: &'static Location<'static>,
+//!   }
+//!
+//!   #[derive(Error, Debug)]
+//!   #[error("could not load config")]
+//!   pub struct ConfigError {
+//!       #[from]
+//!       source: ReadError,
+//!       location: &'static Location<'static>,
+//!   }
+//!
+//!   fn read() -> Result<String, ReadError> {
+//!       Ok(std::fs::read_to_string("/nonexistent/config.toml")?)
+//!   }
+//!
+//!   fn load() -> Result<String, ConfigError> {
+//!       Ok(read()?)
+//!   }
+//!
+//!   let error = load().unwrap_err();
+//!   assert_eq!(error.origin_location(), error.source.location());
+//!   for (type_name, location) in error.locations() {
+//!       eprintln!("{type_name} at {location}");
+//!   }
+//!   ```
+//!
+//!   Errors built without a generated `From` impl, such as with
+//!   `map_err(MyError::Parse)` or `Err(MyError::Missing { .. })`, can take the
+//!   caller's location from the [`ResultExt`] methods `.located()`,
+//!   `.map_err_located(f)` and `.or_located(constructor)`. They rely on the
+//!   `set_location()` and `with_location()` methods generated next to
+//!   `.location()`.
+//!
+//!   The [`bail!`], [`ensure!`] and [`err!`] macros do the same for errors
+//!   constructed directly, recording the location of the macro call even
+//!   inside `#[track_caller]` functions. Whatever the location field was
+//!   constructed with, such as `None`, is overwritten.
+//!
+//!   ```rust
+//!   # use std::panic::Location;
+//!   use wherror::{bail, ensure, Error};
+//!
+//!   #[derive(Error, Debug)]
+//!   pub enum StoreError {
+//!       #[error("key {key} not found")]
+//!       NotFound {
+//!           key: String,
+//!           #[location]
+//!           location: Option<&'static Location<'static>>,
+//!       },
+//!       #[error("empty key")]
+//!       Empty(#[location] Option<&'static Location<'static>>),
+//!   }
+//!
+//!   fn get(key: &str) -> Result<Vec<u8>, StoreError> {
+//!       ensure!(!key.is_empty(), StoreError::Empty(None));
+//!       bail!(StoreError::NotFound {
+//!           key: key.to_owned(),
+//!           location: None,
+//!       });
+//!   }
+//!   ```
+//!
+//!   [`ResultExt`]: crate::ResultExt
+//!   [`bail!`]: crate::bail
+//!   [`ensure!`]: crate::ensure
+//!   [`err!`]: crate::err
+//!
+//! - Fields marked `#[error(capture = thread)]` or `#[error(capture = time)]`,
+//!   of type [`wherror::ThreadInfo`] or `std::time::SystemTime` or an `Option`
+//!   of them, are filled in by generated `From` impls with the current thread
+//!   and time, next to the location and backtrace, and read back with the
+//!   generated `.thread()` and `.timestamp()` methods. Unmarked fields of
+//!   these types are left alone.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::time::SystemTime;
+//!   # use wherror::{Error, ThreadInfo};
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum JobError {
+//!       #[error("job failed on {1}")]
+//!       Io(
+//!           #[from] io::Error,
+//!           #[error(capture = thread)] ThreadInfo,
+//!           #[error(capture = time)] Option<SystemTime>,
+//!       ),
+//!   }
+//!
+//!   let error = JobError::from(io::Error::new(io::ErrorKind::Other, "disk full"));
+//!   assert_eq!(error.thread(), Some(&ThreadInfo::current()));
+//!   assert!(error.timestamp().is_some());
+//!   ```
+//!
+//!   [`wherror::ThreadInfo`]: crate::ThreadInfo
+//!
+//! - Any other field can say how generated `From` impls fill it in with
+//!   `#[error(init = expr)]`, for ambient context such as a request ID. The
+//!   expression is evaluated before the source is moved into the error, and
+//!   may refer to it as `source`.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::time::Instant;
+//!   # use wherror::Error;
+//!   #
+//!   # #[derive(Debug)]
+//!   # pub struct RequestId(u64);
+//!   #
+//!   # impl RequestId {
+//!   #     fn current() -> Self {
+//!   #         RequestId(1)
+//!   #     }
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("request {request_id:?} failed: {kind:?}")]
+//!   pub struct RequestError {
+//!       #[from]
+//!       source: io::Error,
+//!       #[error(init = RequestId::current())]
+//!       request_id: RequestId,
+//!       #[error(init = source.kind())]
+//!       kind: io::ErrorKind,
+//!       #[error(init = Instant::now())]
+//!       started: Instant,
+//!   }
+//!   ```
+//!
+//! - A field of type [`wherror::ContextStack`] marked `#[context]`, or an
+//!   `Option` of one, snapshots the contexts entered on the current thread with
+//!   [`context::scope`] or [`context::enter`] when a generated `From` impl
+//!   runs. The alternate `{:#}` form of the message then ends with them.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   use wherror::{context, ContextStack, Error};
+//!
+//!   #[derive(Error, Debug)]
+//!   #[error("failed to load plugin")]
+//!   pub struct PluginError {
+//!       #[from]
+//!       source: io::Error,
+//!       #[context]
+//!       scopes: Option<ContextStack>,
+//!   }
+//!
+//!   let error = context::scope("loading plugin foo", || {
+//!       let _guard = context::enter("parsing manifest");
+//!       PluginError::from(io::Error::new(io::ErrorKind::Other, "disk full"))
+//!   });
+//!   assert_eq!(
+//!       format!("{:#}", error),
+//!       "failed to load plugin, while loading plugin foo → while parsing manifest",
+//!   );
+//!   ```
+//!
+//!   [`wherror::ContextStack`]: crate::ContextStack
+//!
+//! - A field of type [`wherror::ErrorId`] gets a process-unique 64-bit ID in
+//!   generated `From` impls, for correlating the error a client sees with the
+//!   one in the logs. It displays as 16 hex digits, and is returned by the
+//!   generated `error_id()` method. The field is found by its type only when
+//!   spelled `wherror::ErrorId`; otherwise mark it `#[error(id)]`.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   use wherror::{Error, ErrorId};
+//!
+//!   #[derive(Error, Debug)]
+//!   pub enum JobError {
+//!       #[error("job failed (error {1})")]
+//!       Io(#[from] io::Error, #[error(id)] ErrorId),
+//!       #[error("job timed out")]
+//!       Timeout,
+//!   }
+//!
+//!   let error = JobError::from(io::Error::new(io::ErrorKind::Other, "disk full"));
+//!   let id = error.error_id().unwrap();
+//!   assert_eq!(error.to_string(), format!("job failed (error {})", id));
+//!   assert_eq!(JobError::Timeout.error_id(), None);
+//!   ```
+//!
+//!   [`wherror::ErrorId`]: crate::ErrorId
+//!
+//! - One variant of an enum can be marked `#[error(adhoc)]` to hold a free-form
+//!   message in a `String` or `Cow<'static, str>` field. It gets a generated
+//!   `MyError::msg(impl Display)` constructor and `From<&'static str>` and
+//!   `From<String>` impls, which fill in any location and other captured
+//!   fields, so [`format_err!`] and the format string forms of `bail!` and
+//!   `ensure!` can build it. Without a display attribute it displays as the
+//!   message.
+//!
+//!   ```rust
+//!   # use std::panic::Location;
+//!   use wherror::{ensure, Error};
+//!
+//!   #[derive(Error, Debug)]
+//!   pub enum StoreError {
+//!       #[error("key {0} not found")]
+//!       NotFound(String),
+//!       #[error(adhoc)]
+//!       Other(String, &'static Location<'static>),
+//!   }
+//!
+//!   fn put(key: &str) -> Result<(), StoreError> {
+//!       ensure!(!key.is_empty(), "empty key");
+//!       Err(StoreError::msg(format_args!("{} is read-only", key)))
+//!   }
+//!
+//!   assert_eq!(put("").unwrap_err().to_string(), "empty key");
+//!   assert_eq!(put("foo").unwrap_err().to_string(), "foo is read-only");
+//!   ```
+//!
+//!   [`format_err!`]: crate::format_err
+//!
 //! - The Error trait's `provide()` method is implemented to provide whichever field
 //!   has a type named `Backtrace`, if any, as a `std::backtrace::Backtrace`. Using
 //!   `Backtrace` in errors requires a nightly compiler with Rust version 1.73 or
@@ -478,6 +912,316 @@
 //!   }
 //!   ```
 //!
+//! - With the `miette` feature enabled, the derive also implements
+//!   [`miette::Diagnostic`] for errors marked `#[error(diagnostic)]` on the
+//!   struct or enum. Other errors are left alone, so a handwritten or
+//!   `miette`-derived `Diagnostic` impl keeps compiling when some other crate
+//!   in the build turns the feature on. The `code`, `help`, `url` and
+//!   `severity` come from `#[error(...)]` keys, which may be given on the
+//!   struct, on each variant, or on the enum as a default for its variants.
+//!   `help` and `url` interpolate fields just like the error message does.
+//!   Fields may be marked `#[error(label)]` or `#[error(label = "...")]`,
+//!   `#[error(source_code)]`, and `#[error(related)]`, and a location field is
+//!   reported as a related note.
+//!
+//!   ```rust,ignore
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("unexpected token")]
+//!   #[error(diagnostic, code = parser::unexpected, help = "try removing `{token}`")]
+//!   pub struct ParseError {
+//!       token: String,
+//!       #[error(source_code)]
+//!       src: String,
+//!       #[error(label = "unexpected `{token}`")]
+//!       span: (usize, usize),
+//!   }
+//!   ```
+//!
+//!   [`miette::Diagnostic`]: https://docs.rs/miette/latest/miette/trait.Diagnostic.html
+//!
+//! - Errors may map to an HTTP status with `#[error(status = 404)]` on the
+//!   struct, on a variant, or on the enum as the default for its variants
+//!   (otherwise 500). This generates an `http_status()` method and an impl of
+//!   [`HttpStatus`]. Transparent and `#[from]` variants without their own status
+//!   report the status of their source if it has one.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(status = 500)]
+//!   pub enum StoreError {
+//!       #[error("record {0} not found")]
+//!       #[error(status = 404, code = "store::not_found")]
+//!       NotFound(u64),
+//!       #[error(transparent)]
+//!       Io(#[from] io::Error),
+//!   }
+//!
+//!   assert_eq!(StoreError::NotFound(1).http_status(), 404);
+//!   ```
+//!
+//!   With the `problem-json` feature, [`Problem`] renders such an error as an
+//!   RFC 7807 `application/problem+json` body with `type`, `title`, `status`,
+//!   `detail` and `code`. The `http` feature converts a `Problem` into an
+//!   `http::Response`, and the `axum` feature implements axum's `IntoResponse`
+//!   for `Problem`, and for an error type with a status that asks for it with
+//!   `#[error(into_response)]` on the struct or enum.
+//!
+//!   [`HttpStatus`]: crate::HttpStatus
+//!   [`Problem`]: https://docs.rs/wherror/latest/wherror/struct.Problem.html
+//!
+//! - With the `tonic` feature enabled, `#[error(grpc = "NotFound")]` on the
+//!   struct, on a variant, or on the enum as the default for its variants
+//!   (otherwise `Unknown`) generates `From<MyError> for tonic::Status`. The
+//!   status message is the `Display` output, and the `#[error(code = ...)]`
+//!   and the location field are attached as the binary metadata entries
+//!   `wherror-code-bin` and `wherror-location-bin`. Transparent and `#[from]`
+//!   variants without their own code use the code of their source if it has
+//!   one.
+//!
+//!   ```rust,ignore
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(grpc = "Internal")]
+//!   pub enum ServiceError {
+//!       #[error("user {0} not found")]
+//!       #[error(grpc = "NotFound", code = "user::not_found")]
+//!       UserNotFound(u64),
+//!       #[error(transparent)]
+//!       Store(#[from] StoreError),
+//!   }
+//!
+//!   fn get_user(id: u64) -> Result<User, tonic::Status> {
+//!       Ok(lookup(id)?)
+//!   }
+//!   ```
+//!
+//! - Errors for command-line tools may carry `#[error(exit_code = 2)]`, or a
+//!   sysexits name such as `#[error(exit_code = "EX_CONFIG")]`, on the struct,
+//!   on a variant, or on the enum as the default for its variants (otherwise
+//!   1). This generates an `exit_code()` method returning
+//!   [`std::process::ExitCode`] and a [`Termination`] impl that prints the error
+//!   message and its chain of sources to stderr before exiting with that code.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::process::ExitCode;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(exit_code = "EX_SOFTWARE")]
+//!   pub enum CliError {
+//!       #[error("invalid arguments")]
+//!       #[error(exit_code = 2)]
+//!       Usage,
+//!       #[error("failed to read configuration")]
+//!       #[error(exit_code = "EX_CONFIG")]
+//!       Config(#[source] io::Error),
+//!   }
+//!
+//!   # fn load_config() -> Result<(), CliError> { Ok(()) }
+//!   #
+//!   fn main() -> ExitCode {
+//!       wherror::run(|| -> Result<(), CliError> {
+//!           load_config()?;
+//!           Ok(())
+//!       })
+//!   }
+//!   ```
+//!
+//!   The standard library's `Termination` impl for `Result<(), E>` always
+//!   prints `E`'s `Debug` output and exits with 1, so rather than return
+//!   `Result<(), CliError>` from `main`, wrap its body in [`wherror::run`],
+//!   which reports the error and exits with its code.
+//!
+//!   [`wherror::run`]: crate::run
+//!   [`Termination`]: std::process::Termination
+//!
+//! - Transient errors may be marked `#[error(retryable)]`, or
+//!   `#[error(retryable = expr)]` where the expression may refer to the fields,
+//!   which generates an `is_retryable()` method and an impl of [`Retryable`].
+//!   Variants with a transparent or source field and no explicit setting use
+//!   the classification of the source if it implements `Retryable`, which
+//!   `std::io::Error` does based on its `ErrorKind`. Otherwise they use the
+//!   enum's own `#[error(retryable = ...)]`, or false. An expression on the
+//!   enum is evaluated against the fields of whichever variant it applies to,
+//!   so every such variant must have the fields it names.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum FetchError {
+//!       #[error("rate limited")]
+//!       #[error(retryable)]
+//!       RateLimited,
+//!       #[error("server returned {status}")]
+//!       #[error(retryable = *status >= 500)]
+//!       Http { status: u16 },
+//!       #[error("io error")]
+//!       Io(#[from] io::Error),
+//!   }
+//!
+//!   let timeout = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+//!   assert!(FetchError::from(timeout).is_retryable());
+//!   ```
+//!
+//!   [`Retryable`]: crate::Retryable
+//!
+//! - Errors returned through `Read`/`Write` impls may use `#[error(into_io)]`
+//!   on the struct or enum to generate `From<MyError> for std::io::Error` and
+//!   an `io_kind()` method. The `ErrorKind` comes from `#[error(io_kind = ...)]`
+//!   on the struct, on a variant, or on the enum as the default for its
+//!   variants (otherwise `Other`), and the original error is kept as the inner
+//!   payload so that it can be downcast back. Variants with a transparent or
+//!   `#[from]` `io::Error` convert back into that original `io::Error`.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(into_io)]
+//!   pub enum DecodeError {
+//!       #[error("invalid byte {0:#x}")]
+//!       #[error(io_kind = InvalidData)]
+//!       InvalidByte(u8),
+//!       #[error("read failed")]
+//!       Io(#[from] io::Error),
+//!   }
+//!
+//!   let error = io::Error::from(DecodeError::InvalidByte(0xff));
+//!   assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+//!   assert!(error.get_ref().unwrap().is::<DecodeError>());
+//!   ```
+//!
+//! - Enums may use `#[error(accessors)]` to generate, for every variant, an
+//!   `is_<variant>()` predicate, an `as_<variant>()` method returning a
+//!   reference to the single field (or a tuple of references to all of them),
+//!   and an `into_<variant>()` method returning the owned fields, or
+//!   `Err(self)` for other variants. Method names are the variant name in
+//!   snake_case, so `HTTPError` gives `is_http_error()`. Variants whose
+//!   accessors would clash with other generated methods, such as `Source`
+//!   (`into_source()`) or `Retryable` (`is_retryable()`), are rejected.
+//!
+//!   ```rust
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(accessors)]
+//!   pub enum FetchError {
+//!       #[error("timed out after {secs}s on {host}")]
+//!       Timeout { secs: u64, host: String },
+//!       #[error("HTTP {0}")]
+//!       Status(u16),
+//!   }
+//!
+//!   let error = FetchError::Status(503);
+//!   assert!(error.is_status());
+//!   assert_eq!(error.as_status(), Some(&503));
+//!   assert!(error.as_timeout().is_none());
+//!   assert_eq!(error.into_status().unwrap(), 503);
+//!   ```
+//!
+//! - Enums may use `#[error(ext)]` to generate extension traits converting into
+//!   their variants, named after the enum with the enum's visibility.
+//!   `MyErrorOptionExt` gives `Option` an `or_<variant>(fields...)` method for
+//!   every variant without a source, and `MyErrorResultExt` gives `Result` one
+//!   for every variant with a source, taking the error as the source. The
+//!   methods are `#[track_caller]`, take the fields other than the source and
+//!   those filled in automatically, and build the error only on failure,
+//!   capturing the caller's location and backtrace as a `From` impl would.
+//!
+//!   ```rust
+//!   # use std::num::ParseIntError;
+//!   # use std::panic::Location;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(ext)]
+//!   pub enum ConfigError {
+//!       #[error("missing key {key}")]
+//!       Missing {
+//!           key: &'static str,
+//!           location: &'static Location<'static>,
+//!       },
+//!       #[error("invalid port {port:?}")]
+//!       Port {
+//!           port: String,
+//!           source: ParseIntError,
+//!           location: &'static Location<'static>,
+//!       },
+//!   }
+//!
+//!   fn port(value: Option<&str>) -> Result<u16, ConfigError> {
+//!       let value = value.or_missing("port")?;
+//!       value.parse().or_port(value.to_owned())
+//!   }
+//!
+//!   assert_eq!(port(None).unwrap_err().to_string(), "missing key port");
+//!   assert_eq!(port(Some("x")).unwrap_err().to_string(), "invalid port \"x\"");
+//!   ```
+//!
+//! - Large error enums make every `Result` carrying them large. With
+//!   `#[error(boxed)]` on an enum named like `MyErrorRepr`, the derive also
+//!   generates a `MyError(Box<MyErrorRepr>)` newtype, one pointer wide, that
+//!   forwards `Display`, `Debug`, `source()` and `location()` and has a `From`
+//!   impl for each `#[from]` type, still capturing the caller's location before
+//!   boxing. Use `#[error(boxed = pub MyError)]` to pick the newtype's name, or
+//!   to make it more visible than the enum; by default it has the enum's
+//!   visibility. `repr()` and `into_repr()` give back the enum for matching.
+//!   Boxing requires the `std` feature.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::mem::size_of;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(boxed)]
+//!   pub enum ParseErrorRepr {
+//!       #[error("invalid header {0:?}")]
+//!       Header([u8; 64]),
+//!       #[error("read failed")]
+//!       Io(#[from] io::Error),
+//!   }
+//!
+//!   fn parse() -> Result<(), ParseError> {
+//!       Err(io::Error::new(io::ErrorKind::Other, "oh no"))?;
+//!       Ok(())
+//!   }
+//!
+//!   assert_eq!(size_of::<Result<(), ParseError>>(), size_of::<usize>());
+//!   assert!(matches!(parse().unwrap_err().repr(), ParseErrorRepr::Io(_)));
+//!   ```
+//!
+//! - To keep an error type from silently growing or losing auto traits, use
+//!   `#[error(max_size = 24)]` and `#[error(assert(Send, Sync, 'static))]` on
+//!   the struct or enum. These expand to `const` assertions, so a refactor that
+//!   adds a large inline field or an `Rc` fails to compile with an error
+//!   pointing at the attribute. With `#[error(boxed)]` they check the boxed
+//!   newtype. They are not supported on errors with type parameters.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use wherror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(max_size = 24, assert(Send, Sync, 'static))]
+//!   pub enum ConfigError {
+//!       #[error("missing key {0}")]
+//!       MissingKey(&'static str),
+//!       #[error("read failed")]
+//!       Io(#[from] io::Error),
+//!   }
+//!   ```
+//!
 //! - See also the [`anyhow`] library for a convenient single error type to use in
 //!   application code.
 //!
@@ -542,28 +1286,158 @@ extern crate std;
 extern crate std as core;
 
 mod aserror;
+mod capture;
+#[cfg(feature = "std")]
+pub mod context;
+#[cfg(feature = "miette")]
+mod diagnostic;
 mod display;
+#[cfg(feature = "std")]
+mod exit;
+mod ext;
+#[cfg(feature = "std")]
+mod extract;
+#[cfg(feature = "tonic")]
+mod grpc;
+#[cfg(target_has_atomic = "64")]
+mod id;
+mod location;
+mod macros;
+mod origin;
+#[cfg(feature = "problem-json")]
+mod problem;
 #[cfg(error_generic_member_access)]
 mod provide;
+mod retry;
+mod status;
 mod var;
 
 pub use wherror_impl::*;
 
+#[cfg(feature = "std")]
+pub use crate::capture::ThreadInfo;
+#[cfg(feature = "std")]
+pub use crate::context::ContextStack;
+#[cfg(target_has_atomic = "ptr")]
+pub use crate::display::set_display_none;
+#[cfg(feature = "std")]
+pub use crate::exit::run;
+pub use crate::ext::{ResultExt, SetLocation};
+#[cfg(feature = "tonic")]
+pub use crate::grpc::GrpcStatus;
+#[cfg(target_has_atomic = "64")]
+pub use crate::id::ErrorId;
+#[cfg(target_has_atomic = "ptr")]
+pub use crate::location::set_location_path_hook;
+pub use crate::location::{Location, SourceLocation};
+pub use crate::origin::Locations;
+#[cfg(feature = "problem-json")]
+pub use crate::problem::Problem;
+pub use crate::retry::Retryable;
+pub use crate::status::HttpStatus;
+
+// Expands to its input only if the std feature is enabled, for generated
+// items that need std whether or not the deriving crate is no_std.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __if_std {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __if_std {
+    ($($tt:tt)*) => {};
+}
+
+// Expands to a compile error with the given message unless the std feature is
+// enabled, for attributes whose generated items can't be built without it.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_std {
+    ($msg:literal) => {};
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_std {
+    ($msg:literal) => {
+        ::core::compile_error!($msg);
+    };
+}
+
 // Not public API.
 #[doc(hidden)]
 pub mod __private {
+    #[doc(hidden)]
+    pub use crate::__if_std as if_std;
+    #[doc(hidden)]
+    pub use crate::__require_std as require_std;
     #[doc(hidden)]
     pub use crate::aserror::AsDynError;
     #[doc(hidden)]
-    pub use crate::display::AsDisplay;
+    pub use crate::capture::AsCaptured;
+    #[cfg(feature = "std")]
+    #[doc(hidden)]
+    pub use crate::context::fmt_context;
+    #[cfg(feature = "miette")]
+    #[doc(hidden)]
+    pub use crate::diagnostic::LocationNote;
+    #[doc(hidden)]
+    pub use crate::display::{
+        AsDisplay, AsDisplayOption, DisplayFn, DisplayWith, DisplayWithFormatter, DisplayWithValue,
+    };
+    #[cfg(feature = "std")]
+    #[doc(hidden)]
+    pub use crate::exit::report;
+    #[doc(hidden)]
+    pub use crate::ext::{KnownSetLocation, SetLocationOf, UnknownSetLocation};
+    #[cfg(feature = "std")]
+    #[doc(hidden)]
+    pub use crate::extract::{ErrorSource, IntoSource, NoErrorSource};
+    #[cfg(feature = "tonic")]
+    #[doc(hidden)]
+    pub use crate::grpc::{grpc_status, DefaultGrpcCode, GrpcCodeOf, GrpcStatus, SourceGrpcCode};
+    #[doc(hidden)]
+    pub use crate::location::{
+        source_location, type_name_of, AsLocation, FormatLocation, KnownLocation, LocationOf,
+        LocationStyle, UnknownLocation,
+    };
+    #[doc(hidden)]
+    pub use crate::origin::{DefaultLocated, Located, LocatedOf, SourceLocated};
     #[cfg(error_generic_member_access)]
     #[doc(hidden)]
     pub use crate::provide::ThiserrorProvide;
     #[doc(hidden)]
+    pub use crate::retry::{DefaultRetryable, Retryable, RetryableOf, SourceRetryable};
+    #[doc(hidden)]
+    pub use crate::status::{DefaultStatus, HttpStatus, SourceStatus, StatusOf};
+    #[doc(hidden)]
     pub use crate::var::Var;
+    #[cfg(feature = "axum")]
+    #[doc(hidden)]
+    pub use axum_core;
     #[doc(hidden)]
     pub use core::error::Error;
+    #[cfg(feature = "miette")]
+    #[doc(hidden)]
+    pub use miette;
     #[cfg(all(feature = "std", not(thiserror_no_backtrace_type)))]
     #[doc(hidden)]
     pub use std::backtrace::Backtrace;
+    #[cfg(feature = "std")]
+    #[doc(hidden)]
+    pub use std::boxed::Box;
+    #[cfg(feature = "std")]
+    #[doc(hidden)]
+    pub use std::process::{ExitCode, Termination};
+    #[cfg(feature = "tonic")]
+    #[doc(hidden)]
+    pub use tonic;
 }
@@ -0,0 +1,461 @@
+use core::fmt::{self, Debug, Display};
+use core::panic;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(target_has_atomic = "ptr")]
+use core::{mem, ptr};
+
+/// Call-site location of an error that is only kept in debug builds.
+///
+/// A field of this type is filled in by generated `From` impls like a
+/// `&'static Location<'static>` field, but holds the location only when debug
+/// assertions or the `track-location` feature are enabled. Otherwise it is
+/// zero-sized, `get()` returns `None`, and it displays as `<unknown>`.
+///
+/// ```rust
+/// # use std::io;
+/// # use wherror::Error;
+/// #
+/// #[derive(Error, Debug)]
+/// #[error("read failed at {location}")]
+/// pub struct ReadError {
+///     #[from]
+///     source: io::Error,
+///     location: wherror::Location,
+/// }
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    #[cfg(any(debug_assertions, feature = "track-location"))]
+    location: &'static panic::Location<'static>,
+}
+
+impl Location {
+    /// The location of the caller, as with [`core::panic::Location::caller`].
+    #[track_caller]
+    #[inline]
+    pub fn caller() -> Self {
+        Location::from(panic::Location::caller())
+    }
+
+    /// The location, if this build keeps it.
+    #[inline]
+    pub fn get(&self) -> Option<&'static panic::Location<'static>> {
+        #[cfg(any(debug_assertions, feature = "track-location"))]
+        return Some(self.location);
+        #[cfg(not(any(debug_assertions, feature = "track-location")))]
+        return None;
+    }
+}
+
+impl From<&'static panic::Location<'static>> for Location {
+    #[inline]
+    #[allow(unused_variables)]
+    fn from(location: &'static panic::Location<'static>) -> Self {
+        Location {
+            #[cfg(any(debug_assertions, feature = "track-location"))]
+            location,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_location(LocationStyle::Default, formatter)
+    }
+}
+
+impl Debug for Location {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(location) => Debug::fmt(location, formatter),
+            None => formatter.write_str("<unknown>"),
+        }
+    }
+}
+
+// Location returned by the generated location() method for each kind of
+// location field.
+#[doc(hidden)]
+pub trait AsLocation {
+    fn as_location(&self) -> Option<&'static panic::Location<'static>>;
+}
+
+impl AsLocation for &'static panic::Location<'static> {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        Some(self)
+    }
+}
+
+impl AsLocation for Location {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        self.get()
+    }
+}
+
+impl<T: AsLocation> AsLocation for Option<T> {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        self.as_ref()?.as_location()
+    }
+}
+
+// Location of a #[location] field: its own if its type is one of the above
+// under another name, otherwise None. Selected by autoref, so that newtypes
+// which only convert from a location need no AsLocation impl.
+#[doc(hidden)]
+pub struct LocationOf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait KnownLocation {
+    fn as_location(&self) -> Option<&'static panic::Location<'static>>;
+}
+
+impl<T: AsLocation + ?Sized> KnownLocation for LocationOf<'_, T> {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        self.0.as_location()
+    }
+}
+
+#[doc(hidden)]
+pub trait UnknownLocation {
+    fn as_location(&self) -> Option<&'static panic::Location<'static>>;
+}
+
+impl<T: ?Sized> UnknownLocation for &LocationOf<'_, T> {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        None
+    }
+}
+
+/// Call-site location of an error, with the module, function and crate it
+/// was created in.
+///
+/// A field of this type is filled in by generated `From` impls like a
+/// `&'static Location<'static>` field, and by the [`here!`][crate::here]
+/// macro when constructing an error directly. Locations captured by `From`
+/// impls carry the module path and crate of the error type, since
+/// `#[track_caller]` only passes on the file, line and column, and they have
+/// no function name.
+///
+/// Besides `{location}`, which renders like `src/parser.rs:42:13`, error
+/// messages may use `{location:full}` for the location followed by its
+/// function or module path and crate, or any of the other location format
+/// specs.
+///
+/// ```rust
+/// # use wherror::{here, Error, SourceLocation};
+/// #
+/// #[derive(Error, Debug)]
+/// #[error("unexpected end of input at {location:short}")]
+/// pub struct ParseError {
+///     location: SourceLocation,
+/// }
+///
+/// fn parse() -> Result<(), ParseError> {
+///     Err(ParseError { location: here!() })
+/// }
+///
+/// let location = parse().unwrap_err().location;
+/// assert!(location.function().unwrap().ends_with("::parse"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    location: &'static panic::Location<'static>,
+    module_path: &'static str,
+    function: Option<&'static str>,
+    crate_name: Option<&'static str>,
+    crate_version: Option<&'static str>,
+}
+
+impl SourceLocation {
+    /// The file, line and column.
+    #[inline]
+    pub fn location(&self) -> &'static panic::Location<'static> {
+        self.location
+    }
+
+    /// The source file name.
+    #[inline]
+    pub fn file(&self) -> &'static str {
+        self.location.file()
+    }
+
+    /// The line number.
+    #[inline]
+    pub fn line(&self) -> u32 {
+        self.location.line()
+    }
+
+    /// The column number.
+    #[inline]
+    pub fn column(&self) -> u32 {
+        self.location.column()
+    }
+
+    /// The `module_path!()` the location was captured in.
+    #[inline]
+    pub fn module_path(&self) -> &'static str {
+        self.module_path
+    }
+
+    /// The path of the enclosing function, for locations captured by
+    /// [`here!`][crate::here].
+    #[inline]
+    pub fn function(&self) -> Option<&'static str> {
+        self.function
+    }
+
+    /// The `CARGO_PKG_NAME` of the crate the location was captured in.
+    #[inline]
+    pub fn crate_name(&self) -> Option<&'static str> {
+        self.crate_name
+    }
+
+    /// The `CARGO_PKG_VERSION` of the crate the location was captured in.
+    #[inline]
+    pub fn crate_version(&self) -> Option<&'static str> {
+        self.crate_version
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_location(LocationStyle::Default, formatter)
+    }
+}
+
+impl AsLocation for SourceLocation {
+    #[inline]
+    fn as_location(&self) -> Option<&'static panic::Location<'static>> {
+        Some(self.location)
+    }
+}
+
+/// Captures a [`SourceLocation`] for the place where it is invoked.
+///
+/// ```rust
+/// # use wherror::{here, Error, SourceLocation};
+/// #
+/// #[derive(Error, Debug)]
+/// pub enum ConfigError {
+///     #[error("missing key {key} at {location:full}")]
+///     MissingKey {
+///         key: &'static str,
+///         location: SourceLocation,
+///     },
+/// }
+///
+/// let error = ConfigError::MissingKey {
+///     key: "port",
+///     location: here!(),
+/// };
+/// ```
+#[macro_export]
+macro_rules! here {
+    () => {
+        $crate::__private::source_location(
+            ::core::panic::Location::caller(),
+            ::core::module_path!(),
+            ::core::option::Option::Some({
+                fn __here() {}
+                $crate::__private::type_name_of(&__here)
+            }),
+            ::core::option_env!("CARGO_PKG_NAME"),
+            ::core::option_env!("CARGO_PKG_VERSION"),
+        )
+    };
+}
+
+#[doc(hidden)]
+pub fn source_location(
+    location: &'static panic::Location<'static>,
+    module_path: &'static str,
+    function: Option<&'static str>,
+    crate_name: Option<&'static str>,
+    crate_version: Option<&'static str>,
+) -> SourceLocation {
+    let function = function.map(|mut function| {
+        function = function.strip_suffix("::__here").unwrap_or(function);
+        while let Some(outer) = function.strip_suffix("::{{closure}}") {
+            function = outer;
+        }
+        function
+    });
+    SourceLocation {
+        location,
+        module_path,
+        function,
+        crate_name,
+        crate_version,
+    }
+}
+
+#[doc(hidden)]
+pub fn type_name_of<T>(_: &T) -> &'static str {
+    core::any::type_name::<T>()
+}
+
+#[cfg(target_has_atomic = "ptr")]
+static PATH_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets a function applied to file paths when rendering locations in error
+/// messages.
+///
+/// Locations in dependencies carry the absolute path of the cargo registry
+/// checkout, which may be shortened here. The hook applies to `{location}`,
+/// `{location:file}`, `{location:full}` and `{location:link}` in
+/// `#[error(...)]` messages, and to the `Display` impls of [`Location`] and
+/// [`SourceLocation`].
+///
+/// ```rust
+/// wherror::set_location_path_hook(|path| match path.find("/registry/src/") {
+///     Some(registry) => &path[registry + "/registry/src/".len()..],
+///     None => path,
+/// });
+/// ```
+#[cfg(target_has_atomic = "ptr")]
+pub fn set_location_path_hook(hook: fn(&'static str) -> &'static str) {
+    PATH_HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+#[cfg(target_has_atomic = "ptr")]
+fn display_path(path: &'static str) -> &'static str {
+    let hook = PATH_HOOK.load(Ordering::Acquire);
+    if hook.is_null() {
+        return path;
+    }
+    // SAFETY: PATH_HOOK only ever holds a null pointer or a function pointer
+    // stored by set_location_path_hook.
+    let hook = unsafe { mem::transmute::<*mut (), fn(&'static str) -> &'static str>(hook) };
+    hook(path)
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+fn display_path(path: &'static str) -> &'static str {
+    path
+}
+
+// Renderings of a location field selected by the format spec, e.g.
+// {location:short}. Default is a plain {location}.
+#[doc(hidden)]
+#[derive(Copy, Clone)]
+pub enum LocationStyle {
+    Default,
+    File,
+    Line,
+    Short,
+    Full,
+    Link,
+}
+
+#[doc(hidden)]
+pub trait FormatLocation {
+    fn fmt_location(&self, style: LocationStyle, formatter: &mut fmt::Formatter) -> fmt::Result;
+
+    fn styled(&self, style: LocationStyle) -> StyledLocation<'_, Self> {
+        StyledLocation {
+            location: self,
+            style,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct StyledLocation<'a, T: ?Sized> {
+    location: &'a T,
+    style: LocationStyle,
+}
+
+impl<T: FormatLocation + ?Sized> Display for StyledLocation<'_, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.location.fmt_location(self.style, formatter)
+    }
+}
+
+impl<T: FormatLocation + ?Sized> FormatLocation for &T {
+    fn fmt_location(&self, style: LocationStyle, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_location(style, formatter)
+    }
+}
+
+impl FormatLocation for panic::Location<'static> {
+    fn fmt_location(&self, style: LocationStyle, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let file = self.file();
+        match style {
+            LocationStyle::Default | LocationStyle::Full => write!(
+                formatter,
+                "{}:{}:{}",
+                display_path(file),
+                self.line(),
+                self.column(),
+            ),
+            LocationStyle::File => formatter.write_str(display_path(file)),
+            LocationStyle::Line => write!(formatter, "{}", self.line()),
+            LocationStyle::Short => {
+                let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+                write!(formatter, "{}:{}", name, self.line())
+            }
+            LocationStyle::Link => {
+                // OSC 8 hyperlink to the file, around the default rendering.
+                formatter.write_str("\x1b]8;;file://")?;
+                write_absolute_path(file, formatter)?;
+                formatter.write_str("\x1b\\")?;
+                self.fmt_location(LocationStyle::Default, formatter)?;
+                formatter.write_str("\x1b]8;;\x1b\\")
+            }
+        }
+    }
+}
+
+// Paths from file!() are relative to the directory rustc ran in, which for
+// cargo is the workspace root; the closest guess at runtime is the current
+// directory.
+#[cfg(feature = "std")]
+fn write_absolute_path(file: &str, formatter: &mut fmt::Formatter) -> fmt::Result {
+    use std::path::Path;
+
+    if Path::new(file).is_absolute() {
+        return formatter.write_str(file);
+    }
+    match std::env::current_dir() {
+        Ok(dir) => write!(formatter, "{}", dir.join(file).display()),
+        Err(_) => formatter.write_str(file),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn write_absolute_path(file: &str, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str(file)
+}
+
+impl FormatLocation for Location {
+    fn fmt_location(&self, style: LocationStyle, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(location) => location.fmt_location(style, formatter),
+            None => formatter.write_str("<unknown>"),
+        }
+    }
+}
+
+impl FormatLocation for SourceLocation {
+    fn fmt_location(&self, style: LocationStyle, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.location.fmt_location(style, formatter)?;
+        if let LocationStyle::Full = style {
+            let path = self.function.unwrap_or(self.module_path);
+            write!(formatter, " in {}", path)?;
+            if let Some(crate_name) = self.crate_name {
+                write!(formatter, " ({}", crate_name)?;
+                if let Some(crate_version) = self.crate_version {
+                    write!(formatter, " {}", crate_version)?;
+                }
+                formatter.write_str(")")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+/// Evaluates to the given error with its location set to where `err!` is
+/// invoked.
+///
+/// Any location field of the error is overwritten, so the error can be
+/// constructed with a placeholder such as `None` or `Location::caller()`.
+/// Unlike `Location::caller()`, the location recorded is that of the macro
+/// call even inside a `#[track_caller]` function. Errors without a location
+/// field are returned unchanged.
+///
+/// ```rust
+/// # use std::panic::Location;
+/// use wherror::{err, Error};
+///
+/// #[derive(Error, Debug)]
+/// #[error("missing key {key}")]
+/// pub struct MissingKey {
+///     key: &'static str,
+///     #[location]
+///     location: Option<&'static Location<'static>>,
+/// }
+///
+/// let error = err!(MissingKey {
+///     key: "port",
+///     location: None,
+/// });
+/// assert_eq!(error.location().unwrap().line(), line!() - 4);
+/// ```
+#[macro_export]
+macro_rules! err {
+    ($err:expr $(,)?) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{KnownSetLocation as _, UnknownSetLocation as _};
+        let mut __error = $err;
+        (&mut $crate::__private::SetLocationOf(&mut __error))
+            .set_location((|| ::core::panic::Location::caller())());
+        __error
+    }};
+}
+
+/// Returns early with the given error, after setting its location like
+/// [`err!`].
+///
+/// The error is converted with `From` into the error type of the enclosing
+/// function, as `?` would. Given a format string instead, `bail!` returns
+/// [`format_err!`] of it.
+///
+/// [`format_err!`]: crate::format_err
+///
+/// ```rust
+/// # use std::panic::Location;
+/// use wherror::{bail, Error};
+///
+/// #[derive(Error, Debug)]
+/// pub enum StoreError {
+///     #[error("key {key} not found")]
+///     NotFound {
+///         key: String,
+///         #[location]
+///         location: Option<&'static Location<'static>>,
+///     },
+/// }
+///
+/// fn get(key: &str) -> Result<Vec<u8>, StoreError> {
+///     bail!(StoreError::NotFound {
+///         key: key.to_owned(),
+///         location: None,
+///     });
+/// }
+///
+/// assert!(get("foo").unwrap_err().location().is_some());
+/// ```
+#[macro_export]
+macro_rules! bail {
+    ($fmt:literal $(, $($arg:tt)*)?) => {
+        return ::core::result::Result::Err($crate::format_err!($fmt $(, $($arg)*)?))
+    };
+    ($err:expr $(,)?) => {
+        return ::core::result::Result::Err(::core::convert::From::from($crate::err!($err)))
+    };
+}
+
+/// Returns early with the given error if a condition is not true, like
+/// [`bail!`].
+///
+/// ```rust
+/// # use std::panic::Location;
+/// use wherror::{ensure, Error};
+///
+/// #[derive(Error, Debug)]
+/// #[error("invalid port {port}")]
+/// pub struct InvalidPort {
+///     port: u16,
+///     #[location]
+///     location: Option<&'static Location<'static>>,
+/// }
+///
+/// fn check(port: u16) -> Result<u16, InvalidPort> {
+///     ensure!(port != 0, InvalidPort { port, location: None });
+///     Ok(port)
+/// }
+///
+/// assert!(check(0).is_err());
+/// ```
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $fmt:literal $(, $($arg:tt)*)?) => {
+        if !$cond {
+            $crate::bail!($fmt $(, $($arg)*)?);
+        }
+    };
+    ($cond:expr, $err:expr $(,)?) => {
+        if !$cond {
+            $crate::bail!($err);
+        }
+    };
+}
+
+/// Formats a message into an error with an `#[error(adhoc)]` variant, through
+/// its generated `From<String>` impl.
+///
+/// The error type is inferred from the context. A location field of the
+/// ad-hoc variant is set to where the macro is invoked, even inside a
+/// `#[track_caller]` function, like [`err!`].
+///
+/// ```rust
+/// # use std::panic::Location;
+/// use wherror::{format_err, Error};
+///
+/// #[derive(Error, Debug)]
+/// pub enum StoreError {
+///     #[error("key {0} not found")]
+///     NotFound(String),
+///     #[error(adhoc)]
+///     Other(String, &'static Location<'static>),
+/// }
+///
+/// fn open(path: &str) -> Result<(), StoreError> {
+///     Err(format_err!("{} is read-only", path))
+/// }
+///
+/// assert_eq!(open("/etc").unwrap_err().to_string(), "/etc is read-only");
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! format_err {
+    ($($arg:tt)+) => {
+        match ::std::format!($($arg)+) {
+            // Converted inside a closure, as in err!, so that the location
+            // passed to the #[track_caller] From impl is this call site.
+            __message => (|| ::core::convert::From::from(__message))(),
+        }
+    };
+}
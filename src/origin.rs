@@ -0,0 +1,91 @@
+use core::any;
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::panic;
+
+// One layer of a chain of derived errors: its own location, and the source or
+// transparent field to follow if that is itself a derived error. Implemented
+// by #[derive(Error)] for every error type.
+#[doc(hidden)]
+pub trait Located {
+    fn location(&self) -> Option<&'static panic::Location<'static>>;
+
+    fn located_source(&self) -> Option<&dyn Located>;
+
+    fn type_name(&self) -> &'static str {
+        any::type_name::<Self>()
+    }
+}
+
+// Source of a derived error if its type implements Located, otherwise None.
+// Selected by autoref, so that other sources need no bound.
+#[doc(hidden)]
+pub struct LocatedOf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SourceLocated<'a> {
+    fn as_located(&self) -> Option<&'a (dyn Located + 'a)>;
+}
+
+impl<'a, T: Located + 'a> SourceLocated<'a> for LocatedOf<'a, T> {
+    fn as_located(&self) -> Option<&'a (dyn Located + 'a)> {
+        Some(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultLocated<'a> {
+    fn as_located(&self) -> Option<&'a (dyn Located + 'a)>;
+}
+
+impl<'a, T: ?Sized> DefaultLocated<'a> for &LocatedOf<'a, T> {
+    fn as_located(&self) -> Option<&'a (dyn Located + 'a)> {
+        None
+    }
+}
+
+/// Iterator over the locations of an error and of its derived sources.
+///
+/// Returned by the `locations()` method generated for errors with a location
+/// field. Starting from the error itself, follows `#[source]`, `#[from]` and
+/// transparent fields for as long as their type is also derived with
+/// `#[derive(Error)]`, and yields the type name and location of each layer
+/// that has a location. The last item is where the failure began.
+#[derive(Clone)]
+pub struct Locations<'a> {
+    next: Option<&'a (dyn Located + 'a)>,
+}
+
+impl<'a> Locations<'a> {
+    #[doc(hidden)]
+    pub fn new(error: &'a (dyn Located + 'a)) -> Self {
+        Locations { next: Some(error) }
+    }
+
+    #[doc(hidden)]
+    pub fn origin(self) -> Option<&'static panic::Location<'static>> {
+        self.last().map(|(_type_name, location)| location)
+    }
+}
+
+impl Iterator for Locations<'_> {
+    type Item = (&'static str, &'static panic::Location<'static>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(layer) = self.next {
+            self.next = layer.located_source();
+            if let Some(location) = layer.location() {
+                return Some((layer.type_name(), location));
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for Locations<'_> {}
+
+impl Debug for Locations<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_list().entries(self.clone()).finish()
+    }
+}
@@ -0,0 +1,201 @@
+use crate::status::HttpStatus;
+use core::fmt::{self, Display, Write as _};
+use std::borrow::Cow;
+use std::string::{String, ToString};
+
+/// An [RFC 7807] problem details object built from an error.
+///
+/// The `status` and `code` come from the error's [`HttpStatus`] impl, the
+/// `title` is the standard reason phrase of the status, and the `detail` is
+/// the error's `Display` message.
+///
+/// [RFC 7807]: https://www.rfc-editor.org/rfc/rfc7807
+///
+/// ```
+/// # use wherror::{Error, Problem};
+/// #
+/// #[derive(Error, Debug)]
+/// #[error("no user with id {0}")]
+/// #[error(status = 404, code = "user::not_found")]
+/// pub struct UserNotFound(u64);
+///
+/// let problem = Problem::new(&UserNotFound(7));
+/// assert_eq!(
+///     problem.to_json(),
+///     r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"no user with id 7","code":"user::not_found"}"#,
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// URI reference identifying the problem type; `about:blank` by default.
+    pub problem_type: Cow<'static, str>,
+    /// Short summary of the problem type.
+    pub title: Cow<'static, str>,
+    /// HTTP status code.
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem.
+    pub detail: String,
+    /// Machine-readable error code, serialized as the `code` extension member.
+    pub code: Option<&'static str>,
+}
+
+impl Problem {
+    /// Media type of the JSON serialization.
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    /// Builds the problem details of an error.
+    pub fn new<E>(error: &E) -> Self
+    where
+        E: HttpStatus + Display + ?Sized,
+    {
+        let status = error.http_status();
+        Problem {
+            problem_type: Cow::Borrowed("about:blank"),
+            title: Cow::Borrowed(reason_phrase(status)),
+            status,
+            detail: error.to_string(),
+            code: error.error_code(),
+        }
+    }
+
+    /// Replaces the `about:blank` problem type.
+    pub fn with_type(mut self, problem_type: impl Into<Cow<'static, str>>) -> Self {
+        self.problem_type = problem_type.into();
+        self
+    }
+
+    /// Serializes the problem as an `application/problem+json` body.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        json.push_str("{\"type\":");
+        push_json_str(&mut json, &self.problem_type);
+        json.push_str(",\"title\":");
+        push_json_str(&mut json, &self.title);
+        let _ = write!(json, ",\"status\":{}", self.status);
+        json.push_str(",\"detail\":");
+        push_json_str(&mut json, &self.detail);
+        if let Some(code) = self.code {
+            json.push_str(",\"code\":");
+            push_json_str(&mut json, code);
+        }
+        json.push('}');
+        json
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.to_json())
+    }
+}
+
+fn push_json_str(json: &mut String, value: &str) {
+    json.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch < ' ' => {
+                let _ = write!(json, "\\u{:04x}", ch as u32);
+            }
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        102 => "Processing",
+        103 => "Early Hints",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        203 => "Non-Authoritative Information",
+        204 => "No Content",
+        205 => "Reset Content",
+        206 => "Partial Content",
+        300 => "Multiple Choices",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        407 => "Proxy Authentication Required",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        411 => "Length Required",
+        412 => "Precondition Failed",
+        413 => "Content Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        416 => "Range Not Satisfiable",
+        417 => "Expectation Failed",
+        418 => "I'm a teapot",
+        421 => "Misdirected Request",
+        422 => "Unprocessable Content",
+        423 => "Locked",
+        424 => "Failed Dependency",
+        425 => "Too Early",
+        426 => "Upgrade Required",
+        428 => "Precondition Required",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        451 => "Unavailable For Legal Reasons",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        505 => "HTTP Version Not Supported",
+        506 => "Variant Also Negotiates",
+        507 => "Insufficient Storage",
+        508 => "Loop Detected",
+        511 => "Network Authentication Required",
+        _ => "Unknown Status",
+    }
+}
+
+#[cfg(feature = "http")]
+impl Problem {
+    /// Builds an HTTP response carrying the problem as its JSON body.
+    pub fn to_response(&self) -> http::Response<String> {
+        let status = http::StatusCode::from_u16(self.status)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = http::Response::new(self.to_json());
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(Problem::CONTENT_TYPE),
+        );
+        response
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<Problem> for http::Response<String> {
+    fn from(problem: Problem) -> Self {
+        problem.to_response()
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for Problem {
+    fn into_response(self) -> axum_core::response::Response {
+        self.to_response().map(axum_core::body::Body::from)
+    }
+}
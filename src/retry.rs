@@ -0,0 +1,63 @@
+/// Whether an error is transient.
+///
+/// Implemented by `#[derive(Error)]` for errors that carry an
+/// `#[error(retryable)]` or `#[error(retryable = ...)]` attribute on the type
+/// or on any of its variants, and by `std::io::Error` based on its
+/// [`ErrorKind`][std::io::ErrorKind].
+pub trait Retryable {
+    /// Whether the operation that failed with this error may succeed if
+    /// retried.
+    fn is_retryable(&self) -> bool;
+}
+
+impl<T: Retryable + ?Sized> Retryable for &T {
+    fn is_retryable(&self) -> bool {
+        (**self).is_retryable()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Retryable for std::io::Error {
+    fn is_retryable(&self) -> bool {
+        use std::io::ErrorKind;
+
+        matches!(
+            self.kind(),
+            ErrorKind::Interrupted
+                | ErrorKind::WouldBlock
+                | ErrorKind::TimedOut
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::BrokenPipe
+        )
+    }
+}
+
+// Classification of a transparent or source field: its own if it implements
+// Retryable, otherwise the default of the containing error.
+#[doc(hidden)]
+pub struct RetryableOf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SourceRetryable {
+    fn source_retryable(&self, default: bool) -> bool;
+}
+
+impl<T: Retryable + ?Sized> SourceRetryable for RetryableOf<'_, T> {
+    fn source_retryable(&self, _default: bool) -> bool {
+        self.0.is_retryable()
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultRetryable {
+    fn source_retryable(&self, default: bool) -> bool;
+}
+
+impl<T: ?Sized> DefaultRetryable for &RetryableOf<'_, T> {
+    fn source_retryable(&self, default: bool) -> bool {
+        default
+    }
+}
@@ -0,0 +1,51 @@
+/// HTTP status of an error.
+///
+/// Implemented by `#[derive(Error)]` for errors that carry an
+/// `#[error(status = ...)]` attribute on the type or on any of its variants.
+pub trait HttpStatus {
+    /// The HTTP status code, for example `404`.
+    fn http_status(&self) -> u16;
+
+    /// The machine-readable error code from `#[error(code = ...)]`, if any.
+    fn error_code(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl<T: HttpStatus + ?Sized> HttpStatus for &T {
+    fn http_status(&self) -> u16 {
+        (**self).http_status()
+    }
+
+    fn error_code(&self) -> Option<&'static str> {
+        (**self).error_code()
+    }
+}
+
+// Status of a transparent or #[from] source: its own status if it implements
+// HttpStatus, otherwise the default of the containing error. Selected by
+// autoref, so that sources without a status need no bound.
+#[doc(hidden)]
+pub struct StatusOf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait SourceStatus {
+    fn source_status(&self, default: u16) -> u16;
+}
+
+impl<T: HttpStatus + ?Sized> SourceStatus for StatusOf<'_, T> {
+    fn source_status(&self, _default: u16) -> u16 {
+        self.0.http_status()
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultStatus {
+    fn source_status(&self, default: u16) -> u16;
+}
+
+impl<T: ?Sized> DefaultStatus for &StatusOf<'_, T> {
+    fn source_status(&self, default: u16) -> u16 {
+        default
+    }
+}
@@ -0,0 +1,77 @@
+use std::io;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum Error {
+    #[error("timed out after {secs}s on {host}")]
+    Timeout { secs: u64, host: String },
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[error("HTTP error")]
+    HTTPError(u16, String),
+    #[error("closed")]
+    Closed,
+    #[error("utf8")]
+    Utf8Error,
+}
+
+fn timeout() -> Error {
+    Error::Timeout {
+        secs: 30,
+        host: "example.com".to_owned(),
+    }
+}
+
+#[test]
+fn test_is() {
+    assert!(timeout().is_timeout());
+    assert!(!timeout().is_closed());
+    assert!(Error::Closed.is_closed());
+    assert!(Error::HTTPError(500, String::new()).is_http_error());
+    assert!(Error::Utf8Error.is_utf8_error());
+}
+
+#[test]
+fn test_as() {
+    let error = timeout();
+    assert_eq!(Some((&30, &"example.com".to_owned())), error.as_timeout());
+    assert_eq!(None, error.as_http_error());
+    assert_eq!(Some(()), Error::Closed.as_closed());
+
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert_eq!("oh no", error.as_io().unwrap().to_string());
+}
+
+#[test]
+fn test_into() {
+    let (secs, host) = timeout().into_timeout().unwrap();
+    assert_eq!(30, secs);
+    assert_eq!("example.com", host);
+
+    let error = Error::HTTPError(404, "not found".to_owned());
+    let error = error.into_timeout().unwrap_err();
+    assert_eq!(
+        (404, "not found".to_owned()),
+        error.into_http_error().unwrap()
+    );
+
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let io: io::Error = error.into_io().unwrap();
+    assert_eq!(io::ErrorKind::Other, io.kind());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error(accessors)]
+    enum Error<T> {
+        #[error("value")]
+        Value(T),
+        #[error("r#type")]
+        r#Type { r#type: T },
+    }
+
+    assert_eq!(Some(&1), Error::Value(1).as_value());
+    assert_eq!(2, Error::r#Type { r#type: 2 }.into_type().unwrap());
+}
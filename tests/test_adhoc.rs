@@ -0,0 +1,93 @@
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::io;
+use std::panic::Location;
+use wherror::{bail, Error};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("key {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(adhoc)]
+    Other(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("invalid config: {message}")]
+    #[error(adhoc)]
+    Invalid {
+        message: Cow<'static, str>,
+        location: &'static Location<'static>,
+    },
+}
+
+fn store(fail: bool) -> Result<(), StoreError> {
+    if fail {
+        bail!("store unavailable");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_msg() {
+    let error = StoreError::msg(format_args!("{} is read-only", "foo"));
+    assert!(matches!(&error, StoreError::Other(message) if message == "foo is read-only"));
+    assert_eq!(error.to_string(), "foo is read-only");
+}
+
+#[test]
+fn test_from() {
+    let error = StoreError::from("oh no");
+    assert_eq!(error.to_string(), "oh no");
+
+    let error = StoreError::from(format!("{} failed", "write"));
+    assert_eq!(error.to_string(), "write failed");
+
+    let error = store(true).unwrap_err();
+    assert_eq!(error.to_string(), "store unavailable");
+}
+
+#[test]
+fn test_display_and_location() {
+    let error = ConfigError::from("missing port");
+    assert_eq!(error.to_string(), "invalid config: missing port");
+    assert_eq!(error.location().unwrap().line(), line!() - 2);
+    match &error {
+        ConfigError::Invalid { message, .. } => assert!(matches!(message, Cow::Borrowed(_))),
+    }
+
+    let error = ConfigError::msg(42);
+    assert_eq!(error.to_string(), "invalid config: 42");
+    assert_eq!(error.location().unwrap().line(), line!() - 2);
+}
+
+fn check(port: u16) -> Result<u16, ConfigError> {
+    wherror::ensure!(port != 0, "port {} is reserved", port);
+    Ok(port)
+}
+
+#[test]
+fn test_format() {
+    let error: StoreError = wherror::format_err!("{} is read-only", "foo");
+    assert_eq!(error.to_string(), "foo is read-only");
+
+    let error = check(0).unwrap_err();
+    assert_eq!(error.to_string(), "invalid config: port 0 is reserved");
+    assert_eq!(error.location().unwrap().line(), line!() - 11);
+}
+
+#[track_caller]
+fn check_tracked(port: u16) -> Result<u16, ConfigError> {
+    wherror::ensure!(port != 0, "port {} is reserved", port);
+    Ok(port)
+}
+
+#[test]
+fn test_format_in_track_caller() {
+    let error = check_tracked(0).unwrap_err();
+    assert_eq!(error.location().unwrap().line(), 85);
+}
@@ -0,0 +1,42 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(max_size = 24, assert(Send, Sync, UnwindSafe, RefUnwindSafe, 'static))]
+pub struct Small {
+    len: usize,
+    name: &'static str,
+}
+
+#[derive(Error, Debug)]
+#[error(max_size = 24)]
+#[error(assert(Send, Sync, std::fmt::Debug))]
+pub enum Enum {
+    #[error("io")]
+    Io(#[from] io::Error),
+    #[error("parse {0}")]
+    Parse(u64),
+}
+
+#[derive(Error, Debug)]
+#[error("borrowed {0}")]
+#[error(max_size = 16, assert(Send, Sync))]
+pub struct Borrowed<'a>(&'a str);
+
+#[derive(Error, Debug)]
+#[error(boxed, max_size = 8, assert(Send, Sync))]
+pub enum LargeRepr {
+    #[error("large")]
+    Large([u64; 32]),
+}
+
+#[test]
+fn test_assertions() {
+    // The assertions are checked at compile time; reaching this means they
+    // all held.
+    assert_eq!(std::mem::size_of::<Large>(), 8);
+}
@@ -0,0 +1,97 @@
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use http_body_util::BodyExt as _;
+use tower::ServiceExt as _;
+use wherror::{Error, Problem};
+
+#[derive(Error, Debug)]
+#[error(into_response)]
+enum ApiError {
+    #[error("no user with id {0}")]
+    #[error(status = 404, code = "user::not_found")]
+    NotFound(u64),
+    #[error("teapot")]
+    #[error(status = 418)]
+    Teapot,
+}
+
+async fn user() -> Result<String, ApiError> {
+    Err(ApiError::NotFound(7))
+}
+
+async fn teapot() -> Result<String, ApiError> {
+    Err(ApiError::Teapot)
+}
+
+async fn request(uri: &str) -> (StatusCode, String, String) {
+    let app = Router::new()
+        .route("/user", get(user))
+        .route("/teapot", get(teapot));
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_into_response() {
+    let (status, content_type, body) = request("/user").await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+    assert_eq!(Problem::CONTENT_TYPE, content_type);
+    assert_eq!(
+        r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"no user with id 7","code":"user::not_found"}"#,
+        body,
+    );
+
+    let (status, _content_type, body) = request("/teapot").await;
+    assert_eq!(StatusCode::IM_A_TEAPOT, status);
+    assert_eq!(
+        r#"{"type":"about:blank","title":"I'm a teapot","status":418,"detail":"teapot"}"#,
+        body,
+    );
+}
+
+#[test]
+fn test_http_response() {
+    let response: axum::http::Response<String> = Problem::new(&ApiError::Teapot).into();
+    assert_eq!(418, response.status().as_u16());
+    assert_eq!(
+        Problem::CONTENT_TYPE,
+        response.headers()[header::CONTENT_TYPE],
+    );
+}
+
+#[test]
+fn test_to_response() {
+    let response = Problem::new(&ApiError::NotFound(7)).to_response();
+    assert_eq!(404, response.status().as_u16());
+    assert!(response.body().contains("\"code\":\"user::not_found\""));
+}
+
+#[test]
+fn test_handwritten_into_response() {
+    #[derive(Error, Debug)]
+    #[error("gone")]
+    #[error(status = 410)]
+    struct Gone;
+
+    impl IntoResponse for Gone {
+        fn into_response(self) -> Response {
+            StatusCode::GONE.into_response()
+        }
+    }
+
+    assert_eq!(StatusCode::GONE, Gone.into_response().status());
+}
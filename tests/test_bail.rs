@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::panic::Location;
+use wherror::{bail, ensure, err, Error, SourceLocation};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("key {key} not found")]
+    NotFound {
+        key: String,
+        location: &'static Location<'static>,
+    },
+    #[error("invalid key")]
+    Invalid(#[location] Option<&'static Location<'static>>),
+    #[error("store closed")]
+    Closed,
+}
+
+#[derive(Error, Debug)]
+#[error("request failed at {location}")]
+pub struct RequestError {
+    #[from]
+    source: StoreError,
+    location: SourceLocation,
+}
+
+#[track_caller]
+fn get(key: &str) -> Result<Vec<u8>, StoreError> {
+    bail!(StoreError::NotFound {
+        key: key.to_owned(),
+        location: Location::caller(),
+    });
+}
+
+fn check(key: &str) -> Result<&str, StoreError> {
+    ensure!(!key.is_empty(), StoreError::Invalid(None));
+    Ok(key)
+}
+
+fn request(key: &str) -> Result<(), RequestError> {
+    ensure!(key != "closed", StoreError::Closed);
+    Ok(())
+}
+
+#[test]
+fn test_bail_in_track_caller() {
+    let error = get("foo").unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), 30);
+}
+
+#[test]
+fn test_ensure() {
+    assert_eq!(check("foo").unwrap(), "foo");
+    let error = check("").unwrap_err();
+    assert_eq!(error.location().unwrap().line(), 37);
+}
+
+#[test]
+fn test_err() {
+    let error = err!(StoreError::Invalid(None));
+    assert_eq!(error.location().unwrap().line(), line!() - 1);
+
+    let error = err!(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert_eq!(error.to_string(), "oh no");
+}
+
+#[test]
+fn test_bail_converts() {
+    let error = request("closed").unwrap_err();
+    assert!(matches!(error.source, StoreError::Closed));
+    assert_eq!(error.location.line(), 42);
+}
@@ -0,0 +1,95 @@
+use std::error::Error as _;
+use std::io;
+use std::mem;
+use std::panic::Location;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(boxed)]
+enum ParseErrorRepr {
+    #[error("invalid header {0:?}")]
+    Header([u8; 64]),
+    #[error("read failed at {location}")]
+    Io {
+        #[from]
+        source: io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("bad utf-8")]
+    Utf8(#[from] Box<std::str::Utf8Error>),
+}
+
+#[derive(Error, Debug)]
+#[error(boxed = Wrapped)]
+pub enum Inner<T> {
+    #[error("value {0}")]
+    Value(T),
+}
+
+mod private {
+    use wherror::Error;
+
+    #[derive(Error, Debug)]
+    #[error(boxed = pub PublicError)]
+    enum PrivateRepr {
+        #[error("private")]
+        Private,
+    }
+
+    pub fn error() -> PublicError {
+        PublicError::from(PrivateRepr::Private)
+    }
+}
+
+#[test]
+fn test_size() {
+    assert!(mem::size_of::<ParseErrorRepr>() > 64);
+    assert_eq!(mem::size_of::<ParseError>(), mem::size_of::<usize>());
+    assert_eq!(
+        mem::size_of::<Result<(), ParseError>>(),
+        mem::size_of::<usize>(),
+    );
+}
+
+#[test]
+fn test_forwarding() {
+    let error = ParseError::from(ParseErrorRepr::Header([0; 64]));
+    assert!(error.to_string().starts_with("invalid header [0, 0"));
+    assert!(format!("{:?}", error).starts_with("Header([0, 0"));
+    assert!(error.source().is_none());
+    assert!(error.location().is_none());
+    assert!(matches!(error.repr(), ParseErrorRepr::Header(_)));
+}
+
+#[test]
+fn test_from() {
+    fn read() -> Result<(), ParseError> {
+        Err(io::Error::new(io::ErrorKind::Other, "oh no"))?;
+        Ok(())
+    }
+
+    let expected_line = line!() - 4;
+    let error = read().unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), expected_line);
+    assert_eq!(error.source().unwrap().to_string(), "oh no");
+
+    let bytes = vec![0xff];
+    let utf8 = std::str::from_utf8(&bytes).unwrap_err();
+    let error = ParseError::from(utf8);
+    assert!(matches!(error.into_repr(), ParseErrorRepr::Utf8(_)));
+}
+
+#[test]
+fn test_visibility() {
+    let error: private::PublicError = private::error();
+    assert_eq!(error.to_string(), "private");
+}
+
+#[test]
+fn test_generic() {
+    let error = Wrapped::from(Inner::Value(1));
+    assert_eq!(error.to_string(), "value 1");
+    assert!(matches!(error.into_repr(), Inner::Value(1)));
+}
@@ -0,0 +1,104 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::panic::Location;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use wherror::{Error, ThreadInfo};
+
+#[derive(Error, Debug)]
+#[error("request failed on {thread}")]
+pub struct RequestError {
+    #[from]
+    source: io::Error,
+    #[error(capture = thread)]
+    thread: ThreadInfo,
+    #[error(capture = time)]
+    time: SystemTime,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+pub enum JobError {
+    #[error("job failed")]
+    Io(
+        #[from] io::Error,
+        #[error(capture = thread)] Option<ThreadInfo>,
+        #[error(capture = time)] Option<SystemTime>,
+    ),
+    #[error("job timed out")]
+    Timeout,
+}
+
+#[derive(Error, Debug)]
+#[error("expired at {deadline:?}")]
+pub struct ExpiredError {
+    #[error(capture = time)]
+    created: SystemTime,
+    deadline: SystemTime,
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_struct() {
+    let before = SystemTime::now();
+    let error = thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(|| RequestError::from(io_error()))
+        .unwrap()
+        .join()
+        .unwrap();
+    let thread = error.thread().unwrap();
+    assert_eq!(thread.name(), Some("worker"));
+    assert_ne!(thread.id(), thread::current().id());
+    assert_eq!(error.to_string(), "request failed on thread 'worker'");
+    assert!(error.timestamp().unwrap() >= before);
+    assert!(error.location().is_some());
+}
+
+#[test]
+fn test_enum() {
+    let error = JobError::from(io_error());
+    assert_eq!(error.thread(), Some(&ThreadInfo::current()));
+    assert!(error.timestamp().is_some());
+
+    let error = JobError::Timeout;
+    assert_eq!(error.thread(), None);
+    assert_eq!(error.timestamp(), None);
+}
+
+#[test]
+fn test_marked_field() {
+    let created = SystemTime::UNIX_EPOCH;
+    let error = ExpiredError {
+        created,
+        deadline: created + Duration::from_secs(60),
+    };
+    assert_eq!(error.timestamp(), Some(created));
+}
+
+#[test]
+fn test_unmarked_fields() {
+    #[derive(Error, Debug)]
+    #[error("deadline passed")]
+    struct DeadlineError {
+        #[source]
+        source: io::Error,
+        deadline: Option<SystemTime>,
+    }
+
+    impl DeadlineError {
+        fn timestamp(&self) -> Option<SystemTime> {
+            self.deadline
+        }
+    }
+
+    let error = DeadlineError {
+        source: io_error(),
+        deadline: None,
+    };
+    assert_eq!(error.timestamp(), None);
+}
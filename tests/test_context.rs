@@ -0,0 +1,118 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::thread;
+use wherror::{context, ContextStack, Error};
+
+#[derive(Error, Debug)]
+#[error("failed to read manifest")]
+pub struct ReadError {
+    #[from]
+    source: io::Error,
+    #[context]
+    context: ContextStack,
+}
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("plugin failed")]
+    Io {
+        #[from]
+        source: io::Error,
+        #[context]
+        scopes: Option<ContextStack>,
+    },
+    #[error("plugin {0} not found")]
+    NotFound(String),
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_scope() {
+    let error = context::scope("loading plugin foo", || {
+        context::scope("parsing manifest", || ReadError::from(io_error()))
+    });
+    let contexts = error.context.iter().collect::<Vec<_>>();
+    assert_eq!(contexts, ["loading plugin foo", "parsing manifest"]);
+    assert_eq!(error.to_string(), "failed to read manifest");
+    assert_eq!(
+        format!("{:#}", error),
+        "failed to read manifest, while loading plugin foo → while parsing manifest",
+    );
+    assert!(ContextStack::capture().is_empty());
+}
+
+#[test]
+fn test_guard() {
+    let outer = context::enter("loading plugin foo");
+    let inner = context::enter(format!("parsing {}", "manifest"));
+    assert_eq!(ContextStack::capture().len(), 2);
+    drop(inner);
+    let error = ReadError::from(io_error());
+    drop(outer);
+    assert_eq!(
+        format!("{:#}", error),
+        "failed to read manifest, while loading plugin foo",
+    );
+    assert!(ContextStack::capture().is_empty());
+}
+
+#[test]
+fn test_empty() {
+    let error = ReadError::from(io_error());
+    assert!(error.context.is_empty());
+    assert_eq!(format!("{:#}", error), "failed to read manifest");
+}
+
+#[test]
+fn test_per_thread() {
+    let _guard = context::enter("loading plugin foo");
+    let error = thread::spawn(|| ReadError::from(io_error()))
+        .join()
+        .unwrap();
+    assert!(error.context.is_empty());
+}
+
+#[test]
+fn test_enum() {
+    let error = context::scope("loading plugin foo", || PluginError::from(io_error()));
+    assert_eq!(
+        format!("{:#}", error),
+        "plugin failed, while loading plugin foo",
+    );
+
+    let error = PluginError::Io {
+        source: io_error(),
+        scopes: None,
+    };
+    assert_eq!(format!("{:#}", error), "plugin failed");
+
+    let error = context::scope("loading plugin foo", || {
+        PluginError::NotFound("foo".to_owned())
+    });
+    assert_eq!(format!("{:#}", error), "plugin foo not found");
+}
+
+#[test]
+fn test_unmarked_field() {
+    #[derive(Debug, Default)]
+    struct ContextStack(Vec<String>);
+
+    #[derive(Error, Debug)]
+    #[error("failed")]
+    struct Error {
+        #[source]
+        source: io::Error,
+        context: ContextStack,
+    }
+
+    let error = context::scope("loading plugin foo", || Error {
+        source: io_error(),
+        context: ContextStack::default(),
+    });
+    assert!(error.context.0.is_empty());
+    assert_eq!(format!("{:#}", error), "failed");
+}
@@ -0,0 +1,27 @@
+// Kept in its own test binary because the text can only be set once per
+// process.
+
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("no user {user}")]
+pub struct UserError {
+    user: Option<String>,
+}
+
+#[test]
+fn test_set_display_none() {
+    let error = UserError { user: None };
+    assert_eq!(error.to_string(), "no user none");
+
+    assert_eq!(wherror::set_display_none("-"), Ok(()));
+    assert_eq!(error.to_string(), "no user -");
+
+    assert_eq!(wherror::set_display_none("n/a"), Err("n/a"));
+    assert_eq!(error.to_string(), "no user -");
+
+    let error = UserError {
+        user: Some("alice".to_owned()),
+    };
+    assert_eq!(error.to_string(), "no user alice");
+}
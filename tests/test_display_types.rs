@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("failed to copy {from} to {to} via {tmp} ({name}, {link}, {backup})")]
+pub struct CopyError {
+    from: Cow<'static, Path>,
+    to: Box<Path>,
+    tmp: Arc<Path>,
+    name: OsString,
+    link: Rc<Path>,
+    backup: PathBuf,
+}
+
+#[derive(Error, Debug)]
+pub enum RequestError {
+    #[error("timed out after {elapsed} (retry in {retry_after})")]
+    Timeout {
+        elapsed: Duration,
+        retry_after: Option<u64>,
+    },
+    #[error("unknown hosts {0}")]
+    Hosts(Vec<String>),
+    #[error("no user {user}")]
+    User { user: Option<&'static str> },
+    #[error("cannot read {path} (cached {cache}, waited {waited})")]
+    Read {
+        path: Option<PathBuf>,
+        cache: Option<Box<Path>>,
+        waited: Option<Duration>,
+    },
+}
+
+#[test]
+fn test_paths() {
+    let error = CopyError {
+        from: Cow::Borrowed(Path::new("/a")),
+        to: Path::new("/b").into(),
+        tmp: Path::new("/tmp/c").into(),
+        name: OsString::from("c"),
+        link: Path::new("/d").into(),
+        backup: PathBuf::from("/e"),
+    };
+    assert_eq!(
+        error.to_string(),
+        "failed to copy /a to /b via /tmp/c (c, /d, /e)",
+    );
+}
+
+#[test]
+fn test_duration_and_option() {
+    let error = RequestError::Timeout {
+        elapsed: Duration::from_millis(1500),
+        retry_after: Some(3),
+    };
+    assert_eq!(error.to_string(), "timed out after 1.5s (retry in 3)");
+
+    let error = RequestError::User { user: None };
+    assert_eq!(error.to_string(), "no user none");
+}
+
+#[test]
+fn test_option_without_display() {
+    let error = RequestError::Read {
+        path: Some(PathBuf::from("/a")),
+        cache: Some(Path::new("/b").into()),
+        waited: Some(Duration::from_secs(2)),
+    };
+    assert_eq!(error.to_string(), "cannot read /a (cached /b, waited 2s)");
+
+    let error = RequestError::Read {
+        path: None,
+        cache: None,
+        waited: None,
+    };
+    assert_eq!(
+        error.to_string(),
+        "cannot read none (cached none, waited none)",
+    );
+}
+
+#[test]
+fn test_vec() {
+    let error = RequestError::Hosts(vec!["a".to_owned(), "b".to_owned()]);
+    assert_eq!(error.to_string(), "unknown hosts a, b");
+
+    let error = RequestError::Hosts(Vec::new());
+    assert_eq!(error.to_string(), "unknown hosts ");
+}
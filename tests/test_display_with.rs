@@ -0,0 +1,86 @@
+#![allow(dead_code)]
+
+use std::fmt;
+use wherror::Error;
+
+fn encode<T: AsRef<[u8]>>(data: T) -> String {
+    data.as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Frame {
+    data: Vec<u8>,
+}
+
+fn fmt_len(frame: &Frame, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{} bytes", frame.data.len())
+}
+
+fn comma_separated<T: AsRef<[String]>>(items: T) -> String {
+    items.as_ref().join(", ")
+}
+
+pub struct NoDisplay(u8);
+
+mod adapters {
+    pub fn no_display(value: &super::NoDisplay) -> u8 {
+        value.0
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("bad checksum {checksum} over {payload:?}")]
+pub struct ChecksumError {
+    #[error(display_with = encode)]
+    checksum: Vec<u8>,
+    #[error(display_with = encode)]
+    payload: Vec<u8>,
+}
+
+#[derive(Error, Debug)]
+pub enum FrameError {
+    #[error("frame of {0} is too long")]
+    TooLong(#[error(display_with = fmt_len)] Frame),
+    #[error("unknown tags: {tags}")]
+    Tags {
+        #[error(display_with = comma_separated)]
+        tags: Vec<String>,
+    },
+    #[error("opcode {0:>4}")]
+    Opcode(#[error(display_with = adapters::no_display)] NoDisplay),
+}
+
+impl fmt::Debug for NoDisplay {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "NoDisplay({})", self.0)
+    }
+}
+
+#[test]
+fn test_value_adapter() {
+    let error = ChecksumError {
+        checksum: vec![0xde, 0xad],
+        payload: vec![1, 2],
+    };
+    assert_eq!(error.to_string(), "bad checksum dead over [1, 2]");
+
+    let error = FrameError::Tags {
+        tags: vec!["a".to_owned(), "b".to_owned()],
+    };
+    assert_eq!(error.to_string(), "unknown tags: a, b");
+}
+
+#[test]
+fn test_formatter_adapter() {
+    let error = FrameError::TooLong(Frame { data: vec![0; 3] });
+    assert_eq!(error.to_string(), "frame of 3 bytes is too long");
+}
+
+#[test]
+fn test_format_spec() {
+    let error = FrameError::Opcode(NoDisplay(7));
+    assert_eq!(error.to_string(), "opcode    7");
+}
@@ -0,0 +1,87 @@
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::io;
+use std::thread;
+use wherror::{Error, ErrorId};
+
+#[derive(Error, Debug)]
+#[error("request failed (error {id})")]
+pub struct RequestError {
+    #[from]
+    source: io::Error,
+    id: wherror::ErrorId,
+}
+
+#[derive(Error, Debug)]
+pub enum JobError {
+    #[error("job failed")]
+    Io(#[from] io::Error, #[error(id)] Option<ErrorId>),
+    #[error("job timed out")]
+    Timeout,
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_struct() {
+    let error = RequestError::from(io_error());
+    let id = error.error_id().unwrap();
+    assert_eq!(id, error.id);
+    assert_eq!(
+        error.to_string(),
+        format!("request failed (error {:016x})", id.as_u64()),
+    );
+}
+
+#[test]
+fn test_enum() {
+    let error = JobError::from(io_error());
+    assert!(error.error_id().is_some());
+    assert_eq!(JobError::Timeout.error_id(), None);
+}
+
+#[test]
+fn test_unique() {
+    let threads = (0..4)
+        .map(|_| thread::spawn(|| (0..1000).map(|_| ErrorId::new()).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    let mut ids = HashSet::new();
+    for thread in threads {
+        let batch = thread.join().unwrap();
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+        ids.extend(batch);
+    }
+    assert_eq!(ids.len(), 4000);
+}
+
+#[test]
+fn test_round_trip() {
+    let id = ErrorId::from_u64(0xdead_beef);
+    assert_eq!(id.to_string(), "00000000deadbeef");
+    assert_eq!(format!("{:?}", id), "ErrorId(00000000deadbeef)");
+    assert_eq!(u64::from(id), 0xdead_beef);
+}
+
+#[test]
+fn test_unrelated_error_id() {
+    #[derive(Debug, PartialEq)]
+    struct ErrorId(u32);
+
+    #[derive(Error, Debug)]
+    #[error("error {}", .id.0)]
+    struct Error {
+        #[source]
+        source: io::Error,
+        id: ErrorId,
+    }
+
+    let error = Error {
+        source: io_error(),
+        id: ErrorId(7),
+    };
+    assert_eq!(error.to_string(), "error 7");
+    assert_eq!(error.id, ErrorId(7));
+}
@@ -0,0 +1,81 @@
+use std::io;
+use std::process::{ExitCode, Termination};
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(exit_code = "EX_SOFTWARE")]
+pub enum CliError {
+    #[error("invalid arguments")]
+    #[error(exit_code = 2)]
+    Usage,
+    #[error("bad configuration")]
+    #[error(exit_code = "EX_CONFIG")]
+    Config(#[source] io::Error),
+    #[error("internal error")]
+    Internal,
+}
+
+#[test]
+fn test_enum() {
+    assert_eq!(ExitCode::from(2), CliError::Usage.exit_code());
+    let io = io::Error::new(io::ErrorKind::NotFound, "config.toml");
+    assert_eq!(ExitCode::from(78), CliError::Config(io).exit_code());
+    assert_eq!(ExitCode::from(70), CliError::Internal.exit_code());
+}
+
+#[test]
+fn test_enum_without_default() {
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error("interrupted")]
+        #[error(exit_code = 130)]
+        Interrupted,
+        #[error("failed")]
+        Failed,
+    }
+
+    assert_eq!(ExitCode::from(130), Error::Interrupted.exit_code());
+    assert_eq!(ExitCode::FAILURE, Error::Failed.exit_code());
+}
+
+#[test]
+fn test_termination() {
+    #[derive(Error, Debug)]
+    #[error("no input file")]
+    #[error(exit_code = "EX_NOINPUT")]
+    struct NoInput {
+        source: io::Error,
+    }
+
+    let error = NoInput {
+        source: io::Error::new(io::ErrorKind::NotFound, "input.txt"),
+    };
+    assert_eq!(ExitCode::from(66), error.exit_code());
+    assert_eq!(ExitCode::from(66), error.report());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error("bad value {0}")]
+    #[error(exit_code = "EX_DATAERR")]
+    struct BadValue<T>(T);
+
+    assert_eq!(ExitCode::from(65), BadValue(1).exit_code());
+    assert_eq!(ExitCode::from(65), BadValue("x").report());
+}
+
+#[test]
+fn test_run() {
+    fn load_config(path: &str) -> io::Result<String> {
+        Err(io::Error::new(io::ErrorKind::NotFound, path))
+    }
+
+    assert_eq!(ExitCode::SUCCESS, wherror::run(|| Ok::<(), CliError>(())));
+    assert_eq!(ExitCode::from(2), wherror::run(|| Err(CliError::Usage)));
+    let code = wherror::run(|| -> Result<(), CliError> {
+        load_config("config.toml").map_err(CliError::Config)?;
+        Ok(())
+    });
+    assert_eq!(ExitCode::from(78), code);
+}
@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+
+use std::num::ParseIntError;
+use std::panic::Location;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(ext)]
+pub enum ConfigError {
+    #[error("missing key {name}")]
+    Missing {
+        name: String,
+        location: &'static Location<'static>,
+    },
+    #[error("empty config")]
+    Empty,
+    #[error("invalid port in {0}")]
+    Port(String, #[source] ParseIntError, &'static Location<'static>),
+    #[error(transparent)]
+    Io(std::io::Error),
+}
+
+#[test]
+fn test_option_ext() {
+    let value = Some(8080).or_missing("port".to_owned()).unwrap();
+    assert_eq!(value, 8080);
+
+    let error = None::<u16>.or_missing("port".to_owned()).unwrap_err();
+    assert_eq!(error.to_string(), "missing key port");
+    assert_eq!(error.location().unwrap().line(), line!() - 2);
+
+    let error = None::<u16>.or_empty().unwrap_err();
+    assert!(matches!(error, ConfigError::Empty));
+}
+
+#[test]
+fn test_result_ext() {
+    let error = "x"
+        .parse::<u16>()
+        .or_port("main.toml".to_owned())
+        .unwrap_err();
+    assert_eq!(error.to_string(), "invalid port in main.toml");
+    assert_eq!(error.location().unwrap().line(), line!() - 3);
+
+    let result: Result<(), std::io::Error> = Err(std::io::ErrorKind::NotFound.into());
+    let error = result.or_io().unwrap_err();
+    assert_eq!(error.to_string(), "entity not found");
+}
@@ -0,0 +1,110 @@
+use std::io;
+use std::panic::Location;
+use tonic::{Code, Status};
+use wherror::{Error, GrpcStatus};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("record {0} not found")]
+    #[error(grpc = "NotFound", code = "store::not_found")]
+    NotFound(u64),
+    #[error("record is locked")]
+    #[error(grpc = Aborted)]
+    Locked,
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+fn metadata(status: &Status, key: &str) -> Option<Vec<u8>> {
+    let value = status.metadata().get_bin(key)?;
+    Some(value.to_bytes().unwrap().to_vec())
+}
+
+#[test]
+fn test_enum() {
+    let status = Status::from(StoreError::NotFound(1));
+    assert_eq!(Code::NotFound, status.code());
+    assert_eq!("record 1 not found", status.message());
+    assert_eq!(
+        Some(b"store::not_found".to_vec()),
+        metadata(&status, "wherror-code-bin"),
+    );
+    assert_eq!(None, metadata(&status, "wherror-location-bin"));
+
+    assert_eq!(Code::Aborted, StoreError::Locked.grpc_code());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    let status = Status::from(StoreError::from(io));
+    assert_eq!(Code::Unknown, status.code());
+    assert_eq!(None, metadata(&status, "wherror-code-bin"));
+}
+
+#[test]
+fn test_struct_location() {
+    #[derive(Error, Debug)]
+    #[error("permission denied")]
+    #[error(grpc = "PermissionDenied")]
+    struct Denied {
+        #[from]
+        source: io::Error,
+        location: &'static Location<'static>,
+    }
+
+    let io = io::Error::new(io::ErrorKind::PermissionDenied, "oh no");
+    let error = Denied::from(io);
+    let location = error.location.to_string();
+    let status = Status::from(error);
+    assert_eq!(Code::PermissionDenied, status.code());
+    assert_eq!(
+        Some(location.into_bytes()),
+        metadata(&status, "wherror-location-bin"),
+    );
+}
+
+#[test]
+fn test_delegate() {
+    #[derive(Error, Debug)]
+    #[error(grpc = "Internal")]
+    enum ServiceError {
+        #[error("store failed")]
+        Store(#[from] StoreError),
+        #[error(transparent)]
+        Upstream(Status),
+        #[error("io failed")]
+        Io(#[from] io::Error),
+        #[error("invalid")]
+        #[error(grpc = "InvalidArgument")]
+        Invalid,
+    }
+
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    #[error(grpc = "Unavailable")]
+    struct Wrapper(Box<StoreError>);
+
+    let error = ServiceError::from(StoreError::NotFound(1));
+    assert_eq!(Code::NotFound, Status::from(error).code());
+
+    let error = ServiceError::Upstream(Status::deadline_exceeded("slow"));
+    assert_eq!(Code::DeadlineExceeded, error.grpc_code());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    assert_eq!(Code::Internal, ServiceError::from(io).grpc_code());
+    assert_eq!(Code::InvalidArgument, ServiceError::Invalid.grpc_code());
+
+    let error = Wrapper(Box::new(StoreError::Locked));
+    assert_eq!(Code::Aborted, error.grpc_code());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error("conflict on {0}")]
+    #[error(grpc = "AlreadyExists")]
+    struct Conflict<T>(T);
+
+    let status = Status::from(Conflict("key"));
+    assert_eq!(Code::AlreadyExists, status.code());
+    assert_eq!("conflict on key", status.message());
+    assert_eq!(Code::AlreadyExists, GrpcStatus::grpc_code(&Conflict(1)));
+}
@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::io;
+use std::panic::Location;
+use std::time::{Instant, SystemTime};
+use wherror::Error;
+
+thread_local! {
+    static REQUEST_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    fn current() -> Self {
+        RequestId(REQUEST_ID.with(Cell::get))
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("request {request_id:?} failed")]
+pub struct RequestError {
+    #[from]
+    source: io::Error,
+    #[error(init = RequestId::current())]
+    request_id: RequestId,
+    #[error(init = Instant::now())]
+    started: Instant,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("fetch failed")]
+    Io(
+        #[from] io::Error,
+        #[error(init = source.kind())] io::ErrorKind,
+        #[error(init = SystemTime::UNIX_EPOCH)] SystemTime,
+    ),
+    #[error("boxed fetch failed")]
+    Boxed {
+        #[from]
+        source: Box<std::fmt::Error>,
+        #[error(init = vec![source.to_string()])]
+        context: Vec<String>,
+    },
+}
+
+#[test]
+fn test_init() {
+    REQUEST_ID.with(|id| id.set(7));
+    let before = Instant::now();
+    let error = RequestError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert_eq!(error.request_id, RequestId(7));
+    assert!(error.started >= before);
+    assert_eq!(error.to_string(), "request RequestId(7) failed");
+}
+
+#[test]
+fn test_init_from_source() {
+    let error = FetchError::from(io::Error::new(io::ErrorKind::NotFound, "oh no"));
+    match error {
+        FetchError::Io(_, kind, time) => {
+            assert_eq!(kind, io::ErrorKind::NotFound);
+            assert_eq!(time, SystemTime::UNIX_EPOCH);
+        }
+        FetchError::Boxed { .. } => unreachable!(),
+    }
+
+    let error = FetchError::from(std::fmt::Error);
+    match error {
+        FetchError::Boxed { context, .. } => {
+            assert_eq!(context, ["an error occurred when formatting an argument"])
+        }
+        FetchError::Io(..) => unreachable!(),
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Read};
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(into_io)]
+pub enum DecodeError {
+    #[error("unexpected end of input")]
+    #[error(io_kind = UnexpectedEof)]
+    Truncated,
+    #[error("invalid byte {0:#x}")]
+    #[error(io_kind = InvalidData)]
+    InvalidByte(u8),
+    #[error("unsupported")]
+    Unsupported,
+    #[error("read failed")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn test_kind() {
+    assert_eq!(
+        io::ErrorKind::UnexpectedEof,
+        DecodeError::Truncated.io_kind()
+    );
+    assert_eq!(io::ErrorKind::Other, DecodeError::Unsupported.io_kind());
+
+    let error = io::Error::from(DecodeError::InvalidByte(0xff));
+    assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    assert_eq!("invalid byte 0xff", error.to_string());
+}
+
+#[test]
+fn test_downcast() {
+    let error = io::Error::from(DecodeError::InvalidByte(7));
+    let inner = error.into_inner().unwrap();
+    match *inner.downcast::<DecodeError>().unwrap() {
+        DecodeError::InvalidByte(7) => {}
+        other => panic!("unexpected {other:?}"),
+    }
+}
+
+#[test]
+fn test_unwrap_io() {
+    let original = io::Error::new(io::ErrorKind::PermissionDenied, "oh no");
+    let error = DecodeError::from(original);
+    assert_eq!(io::ErrorKind::PermissionDenied, error.io_kind());
+
+    let error = io::Error::from(error);
+    assert_eq!(io::ErrorKind::PermissionDenied, error.kind());
+    assert_eq!("oh no", error.to_string());
+    assert!(error
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<DecodeError>()
+        .is_none());
+}
+
+#[test]
+fn test_read() {
+    struct Decoder;
+
+    impl Read for Decoder {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(DecodeError::Truncated)?
+        }
+    }
+
+    let error = Decoder.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+}
+
+#[test]
+fn test_enum_default_and_box() {
+    #[derive(Error, Debug)]
+    #[error(into_io, io_kind = InvalidInput)]
+    enum Error {
+        #[error("bad")]
+        Bad,
+        #[error(transparent)]
+        Io(Box<io::Error>),
+    }
+
+    assert_eq!(
+        io::ErrorKind::InvalidInput,
+        io::Error::from(Error::Bad).kind()
+    );
+
+    let original = io::Error::new(io::ErrorKind::TimedOut, "slow");
+    let error = io::Error::from(Error::Io(Box::new(original)));
+    assert_eq!(io::ErrorKind::TimedOut, error.kind());
+    assert_eq!("slow", error.to_string());
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("checksum mismatch")]
+    #[error(into_io, io_kind = InvalidData)]
+    struct ChecksumMismatch;
+
+    #[derive(Error, Debug)]
+    #[error("generic {0}")]
+    #[error(into_io)]
+    struct Generic<T>(T);
+
+    let error = io::Error::from(ChecksumMismatch);
+    assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    assert!(error.get_ref().unwrap().is::<ChecksumMismatch>());
+
+    let error = io::Error::from(Generic(1));
+    assert_eq!(io::ErrorKind::Other, error.kind());
+    assert_eq!("generic 1", error.to_string());
+}
@@ -0,0 +1,109 @@
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::io;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("parse error")]
+pub struct ParseError;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("io error")]
+    Io(#[from(try_from)] io::Error),
+    #[error("parse error")]
+    Parse(#[from(try_from)] Box<ParseError>),
+    #[error("other")]
+    Other {
+        #[source]
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error(transparent)]
+    Anyhow(anyhow::Error),
+    #[error("no source")]
+    Plain,
+}
+
+#[test]
+fn test_try_from() {
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let io = io::Error::try_from(error).unwrap();
+    assert_eq!("oh no", io.to_string());
+
+    let error = Error::from(ParseError);
+    let _: ParseError = ParseError::try_from(error).unwrap();
+
+    let error = io::Error::try_from(Error::Plain).unwrap_err();
+    assert!(matches!(error, Error::Plain));
+}
+
+#[test]
+fn test_into_source() {
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let source = error.into_source().unwrap();
+    assert_eq!("oh no", source.downcast::<io::Error>().unwrap().to_string());
+
+    let source = Error::from(ParseError).into_source().unwrap();
+    assert!(source.is::<ParseError>());
+
+    let other = Error::Other {
+        source: Box::new(ParseError),
+    };
+    assert!(other.into_source().unwrap().is::<ParseError>());
+
+    let source = Error::Anyhow(anyhow::anyhow!("oh no"))
+        .into_source()
+        .unwrap();
+    assert_eq!("oh no", source.to_string());
+
+    assert!(Error::Plain.into_source().is_none());
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("wrapped")]
+    struct Wrapped {
+        #[from(try_from)]
+        source: Option<ParseError>,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("not boxable")]
+    struct Unboxable<T: StdError + 'static>(#[source] T);
+
+    let error = Wrapped::from(ParseError);
+    let _: ParseError = ParseError::try_from(error).unwrap();
+    let error = ParseError::try_from(Wrapped { source: None }).unwrap_err();
+    assert!(error.into_source().is_none());
+    assert!(Wrapped::from(ParseError)
+        .into_source()
+        .unwrap()
+        .is::<ParseError>());
+
+    assert!(Unboxable(ParseError).into_source().is_none());
+}
+
+#[test]
+fn test_without_try_from() {
+    type BoxError = Box<dyn StdError + Send + Sync>;
+
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error("io error")]
+        Io(#[from] io::Error),
+        #[error("boxed")]
+        Boxed(#[from] BoxError),
+    }
+
+    impl From<Error> for io::Error {
+        fn from(error: Error) -> Self {
+            io::Error::new(io::ErrorKind::Other, error)
+        }
+    }
+
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let io = io::Error::from(error);
+    assert_eq!("io error", io.to_string());
+    assert!(Error::from(BoxError::from("oh no")).into_source().is_some());
+}
@@ -152,3 +152,102 @@ fn create_error_helper() -> TestError {
 fn create_error_without_track_caller() -> TestError {
     io::Error::new(io::ErrorKind::AddrInUse, String::new()).into()
 }
+
+#[derive(Error, Debug)]
+enum DebugLocationError {
+    #[error("failed at {location}")]
+    Io {
+        #[from]
+        source: io::Error,
+        location: wherror::Location,
+    },
+    #[error("formatting failed")]
+    Fmt(#[from] std::fmt::Error),
+}
+
+#[test]
+fn test_debug_location() {
+    let line = line!() + 1;
+    let error = DebugLocationError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let location = error.location();
+
+    if cfg!(any(debug_assertions, feature = "track-location")) {
+        let location = location.expect("debug builds keep the location");
+        assert_eq!(location.file(), file!());
+        assert_eq!(location.line(), line);
+        assert_eq!(error.to_string(), format!("failed at {}", location));
+    } else {
+        assert!(location.is_none());
+        assert_eq!(error.to_string(), "failed at <unknown>");
+    }
+
+    let error = DebugLocationError::from(std::fmt::Error);
+    assert!(error.location().is_none());
+}
+
+#[test]
+fn test_debug_location_size() {
+    let expected = if cfg!(any(debug_assertions, feature = "track-location")) {
+        std::mem::size_of::<&'static Location<'static>>()
+    } else {
+        0
+    };
+    assert_eq!(std::mem::size_of::<wherror::Location>(), expected);
+}
+
+#[test]
+fn test_unrelated_location_type() {
+    mod geo {
+        #[derive(Debug, PartialEq)]
+        pub struct Location {
+            pub line: u32,
+            pub col: u32,
+        }
+    }
+
+    #[derive(Error, Debug)]
+    #[error("syntax error at {}:{}", .location.line, .location.col)]
+    struct SyntaxError {
+        #[source]
+        source: io::Error,
+        location: geo::Location,
+    }
+
+    let error = SyntaxError {
+        source: io::Error::new(io::ErrorKind::InvalidData, "oh no"),
+        location: geo::Location { line: 3, col: 7 },
+    };
+    assert_eq!(error.to_string(), "syntax error at 3:7");
+    assert_eq!(error.location, geo::Location { line: 3, col: 7 });
+}
+
+#[test]
+fn test_imported_location_type() {
+    use wherror::{Location, SourceLocation};
+
+    #[derive(Error, Debug)]
+    #[error("io error")]
+    struct IoError {
+        #[from]
+        source: io::Error,
+        location: Location,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("io error")]
+    struct SourceIoError {
+        #[from]
+        source: io::Error,
+        location: SourceLocation,
+    }
+
+    let line = line!() + 1;
+    let error = IoError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    if cfg!(debug_assertions) {
+        assert_eq!(error.location().unwrap().line(), line);
+    }
+
+    let line = line!() + 1;
+    let error = SourceIoError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert_eq!(error.location().unwrap().line(), line);
+}
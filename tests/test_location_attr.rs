@@ -0,0 +1,140 @@
+#![allow(dead_code)]
+
+use std::fmt::{self, Display};
+use std::io;
+use std::panic::Location;
+use wherror::Error;
+
+type Loc = &'static Location<'static>;
+
+#[derive(Debug)]
+pub struct Site(&'static Location<'static>);
+
+impl From<&'static Location<'static>> for Site {
+    fn from(location: &'static Location<'static>) -> Self {
+        Site(location)
+    }
+}
+
+impl Display for Site {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "line {}", self.0.line())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("aliased at {at}")]
+pub struct AliasError {
+    #[from]
+    source: io::Error,
+    #[location]
+    at: Loc,
+}
+
+#[derive(Error, Debug)]
+#[error("newtype at {site}")]
+pub struct NewtypeError {
+    #[from]
+    source: io::Error,
+    #[location]
+    site: Site,
+}
+
+#[derive(Error, Debug)]
+pub enum OptionError {
+    #[error("optional")]
+    Io(
+        #[from] io::Error,
+        #[location] Option<&'static Location<'static>>,
+    ),
+    #[error("optional release location")]
+    Fmt(#[from] fmt::Error, #[location] Option<wherror::Location>),
+}
+
+#[derive(Error, Debug)]
+#[error("renamed")]
+pub struct RenamedError {
+    #[source]
+    source: io::Error,
+    #[location]
+    caller: wherror::Location,
+    other: &'static Location<'static>,
+}
+
+mod site {
+    use std::panic::Location;
+
+    #[derive(Debug)]
+    pub struct SourceLocation(pub &'static Location<'static>);
+
+    impl From<&'static Location<'static>> for SourceLocation {
+        fn from(location: &'static Location<'static>) -> Self {
+            SourceLocation(location)
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("own source location")]
+pub struct OwnSourceLocationError {
+    #[from]
+    source: io::Error,
+    #[location]
+    loc: site::SourceLocation,
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_alias() {
+    let line = line!() + 1;
+    let error = AliasError::from(io_error());
+    assert_eq!(error.location().unwrap().line(), line);
+    assert_eq!(error.to_string(), format!("aliased at {}", error.at));
+}
+
+#[test]
+fn test_newtype() {
+    let line = line!() + 1;
+    let error = NewtypeError::from(io_error());
+    assert_eq!(error.site.0.line(), line);
+    assert_eq!(error.to_string(), format!("newtype at line {}", line));
+    assert_eq!(error.location(), None);
+}
+
+#[test]
+fn test_own_source_location() {
+    let line = line!() + 1;
+    let error = OwnSourceLocationError::from(io_error());
+    assert_eq!(error.loc.0.line(), line);
+    assert_eq!(error.location(), None);
+}
+
+#[test]
+fn test_option() {
+    let line = line!() + 1;
+    let error = OptionError::from(io_error());
+    assert_eq!(error.location().unwrap().line(), line);
+
+    let error = OptionError::from(fmt::Error);
+    match &error {
+        OptionError::Fmt(_, location) => assert!(location.is_some()),
+        OptionError::Io(..) => unreachable!(),
+    }
+    assert_eq!(
+        error.location().is_some(),
+        cfg!(any(debug_assertions, feature = "track-location")),
+    );
+}
+
+#[test]
+fn test_marked_field_wins() {
+    let error = RenamedError {
+        source: io_error(),
+        caller: wherror::Location::caller(),
+        other: Location::caller(),
+    };
+    assert_eq!(error.location(), error.caller.get());
+}
@@ -0,0 +1,70 @@
+use std::panic::Location;
+use wherror::{here, Error, SourceLocation};
+
+#[derive(Error, Debug)]
+#[error("{location} | {location:file} | {location:line} | {location:short}")]
+pub struct Specs {
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+#[error("failed at {location:link}")]
+pub struct Link {
+    location: SourceLocation,
+}
+
+#[derive(Error, Debug)]
+pub enum Mixed {
+    #[error("{0:file}:{0:line} ({0:?})")]
+    Tuple(wherror::Location),
+}
+
+#[test]
+fn test_specs() {
+    let location = Location::caller();
+    let error = Specs { location };
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "{}:{}:{} | {} | {} | test_location_format.rs:{}",
+            file!(),
+            location.line(),
+            location.column(),
+            file!(),
+            location.line(),
+            location.line(),
+        ),
+    );
+}
+
+#[test]
+fn test_link() {
+    let error = Link { location: here!() };
+    let message = error.to_string();
+    let dir = std::env::current_dir().unwrap();
+    let target = dir.join(file!());
+    assert!(message.starts_with(&format!(
+        "failed at \x1b]8;;file://{}\x1b\\",
+        target.display()
+    )));
+    assert!(message.ends_with(&format!(
+        "{}:{}:{}\x1b]8;;\x1b\\",
+        file!(),
+        error.location.line(),
+        error.location.column(),
+    )));
+}
+
+#[test]
+fn test_tuple() {
+    let location = Location::caller();
+    let error = Mixed::Tuple(wherror::Location::from(location));
+    if cfg!(any(debug_assertions, feature = "track-location")) {
+        assert_eq!(
+            error.to_string(),
+            format!("{}:{} ({:?})", file!(), location.line(), location),
+        );
+    } else {
+        assert_eq!(error.to_string(), "<unknown>:<unknown> (<unknown>)");
+    }
+}
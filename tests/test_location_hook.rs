@@ -0,0 +1,36 @@
+use std::panic::Location;
+use wherror::{here, Error, SourceLocation};
+
+#[derive(Error, Debug)]
+#[error("{location} | {location:file} | {location:short}")]
+pub struct Plain {
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+#[error("{location}")]
+pub struct Rich {
+    location: SourceLocation,
+}
+
+// The hook is global, so this is the only test in its binary.
+#[test]
+fn test_path_hook() {
+    wherror::set_location_path_hook(|path| path.strip_prefix("tests/").unwrap_or(path));
+
+    let location = Location::caller();
+    let error = Plain { location };
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "test_location_hook.rs:{}:{} | test_location_hook.rs | test_location_hook.rs:{}",
+            location.line(),
+            location.column(),
+            location.line(),
+        ),
+    );
+
+    let error = Rich { location: here!() };
+    assert!(error.to_string().starts_with("test_location_hook.rs:"));
+    assert_eq!(error.location.file(), "tests/test_location_hook.rs");
+}
@@ -0,0 +1,178 @@
+use miette::{Diagnostic, LabeledSpan, Severity, SourceSpan};
+use std::io;
+use std::panic::Location;
+use wherror::Error;
+
+fn labels(diagnostic: &dyn Diagnostic) -> Vec<LabeledSpan> {
+    diagnostic.labels().into_iter().flatten().collect()
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("unexpected token")]
+    #[error(
+        diagnostic,
+        code = "parser::unexpected",
+        help = "try removing `{token}`"
+    )]
+    #[error(url = "https://example.com/{token}", severity = Warning)]
+    struct ParseError {
+        token: String,
+        #[error(source_code)]
+        src: String,
+        #[error(label = "unexpected `{token}`")]
+        span: (usize, usize),
+        #[error(label)]
+        other: Option<(usize, usize)>,
+    }
+
+    let error = ParseError {
+        token: "}".to_owned(),
+        src: "fn main() {}}".to_owned(),
+        span: (12, 1),
+        other: None,
+    };
+
+    assert_eq!("parser::unexpected", error.code().unwrap().to_string());
+    assert_eq!("try removing `}`", error.help().unwrap().to_string());
+    assert_eq!("https://example.com/}", error.url().unwrap().to_string());
+    assert_eq!(Some(Severity::Warning), error.severity());
+    assert!(error.source_code().is_some());
+    assert!(error.related().is_none());
+
+    let labels = labels(&error);
+    assert_eq!(1, labels.len());
+    assert_eq!(Some("unexpected `}`"), labels[0].label());
+    assert_eq!(12, labels[0].offset());
+    assert_eq!(1, labels[0].len());
+}
+
+#[test]
+fn test_code_path() {
+    #[derive(Error, Debug)]
+    #[error("...")]
+    #[error(diagnostic, code = my_app::config::missing)]
+    struct Error;
+
+    assert_eq!("my_app::config::missing", Error.code().unwrap().to_string());
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Error, Debug)]
+    #[error(diagnostic, code = "app::error", help = "see the manual")]
+    enum Error {
+        #[error("not found: {0}")]
+        #[error(code = "app::not_found", help = "check that {0} exists")]
+        NotFound(String),
+        #[error("unknown")]
+        Unknown,
+        #[error("related")]
+        #[error(severity = Advice)]
+        Related {
+            #[error(related)]
+            others: Vec<Error>,
+        },
+    }
+
+    let error = Error::NotFound("config.toml".to_owned());
+    assert_eq!("app::not_found", error.code().unwrap().to_string());
+    assert_eq!(
+        "check that config.toml exists",
+        error.help().unwrap().to_string(),
+    );
+    assert_eq!(None, error.severity());
+
+    let error = Error::Unknown;
+    assert_eq!("app::error", error.code().unwrap().to_string());
+    assert_eq!("see the manual", error.help().unwrap().to_string());
+    assert!(error.related().is_none());
+
+    let error = Error::Related {
+        others: vec![Error::Unknown, Error::NotFound("x".to_owned())],
+    };
+    assert_eq!(Some(Severity::Advice), error.severity());
+    let related: Vec<String> = error.related().unwrap().map(|d| d.to_string()).collect();
+    assert_eq!(vec!["unknown", "not found: x"], related);
+}
+
+#[test]
+fn test_location_note() {
+    #[derive(Error, Debug)]
+    #[error("io error")]
+    #[error(diagnostic)]
+    struct Error {
+        #[from]
+        source: io::Error,
+        location: &'static Location<'static>,
+    }
+
+    let line = line!() + 1;
+    let error = Error::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    let related: Vec<_> = error.related().unwrap().collect();
+    assert_eq!(1, related.len());
+    assert_eq!(Some(Severity::Advice), related[0].severity());
+    assert_eq!(
+        format!("error occurred at {}:{}:{}", file!(), line, 17),
+        related[0].to_string(),
+    );
+}
+
+#[test]
+fn test_plain() {
+    #[derive(Error, Debug)]
+    #[error("plain")]
+    #[error(diagnostic)]
+    struct Error;
+
+    assert!(Error.code().is_none());
+    assert!(Error.help().is_none());
+    assert!(Error.labels().is_none());
+}
+
+#[test]
+fn test_handwritten() {
+    #[derive(Error, Debug)]
+    #[error("handwritten")]
+    #[error(code = "app::handwritten")]
+    struct Error {
+        location: &'static Location<'static>,
+    }
+
+    impl Diagnostic for Error {
+        fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+            Some(Box::new("written by hand"))
+        }
+    }
+
+    let error = Error {
+        location: Location::caller(),
+    };
+    assert!(error.code().is_none());
+    assert_eq!("written by hand", error.help().unwrap().to_string());
+    assert!(error.related().is_none());
+}
+
+#[test]
+fn test_derived_by_miette() {
+    #[derive(Error, Debug, Diagnostic)]
+    #[error("unexpected token")]
+    #[diagnostic(code(parser::unexpected))]
+    struct ParseError {
+        #[source_code]
+        src: String,
+        #[label("here")]
+        span: SourceSpan,
+    }
+
+    let error = ParseError {
+        src: "fn main() {}}".to_owned(),
+        span: (12, 1).into(),
+    };
+    assert_eq!("parser::unexpected", error.code().unwrap().to_string());
+    assert!(error.source_code().is_some());
+    let labels = labels(&error);
+    assert_eq!(1, labels.len());
+    assert_eq!(Some("here"), labels[0].label());
+}
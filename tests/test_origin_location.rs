@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::panic::Location;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("read failed")]
+pub struct ReadError {
+    #[from]
+    source: io::Error,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct Opaque(#[from] ReadError);
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("could not load config")]
+    Load {
+        #[from]
+        source: Opaque,
+        location: &'static Location<'static>,
+    },
+    #[error("missing key")]
+    Missing {
+        location: &'static Location<'static>,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("startup failed")]
+pub struct StartupError {
+    #[source]
+    source: Box<ConfigError>,
+    location: wherror::Location,
+}
+
+#[derive(Error, Debug)]
+#[error("generic failure")]
+pub struct GenericError<E> {
+    #[source]
+    source: E,
+    location: &'static Location<'static>,
+}
+
+#[derive(Error, Debug)]
+#[error(boxed)]
+pub enum LoadErrorRepr {
+    #[error("load failed")]
+    Config {
+        #[from]
+        source: ConfigError,
+        location: &'static Location<'static>,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("shutting down")]
+    Shutdown,
+}
+
+fn read() -> Result<(), ReadError> {
+    Err(io::Error::new(io::ErrorKind::NotFound, "config.toml"))?;
+    Ok(())
+}
+
+fn load() -> Result<(), ConfigError> {
+    read().map_err(Opaque::from)?;
+    Ok(())
+}
+
+#[test]
+fn test_origin_location() {
+    let error = load().unwrap_err();
+    let inner = match &error {
+        ConfigError::Load { source, .. } => source.0.location,
+        ConfigError::Missing { .. } => unreachable!(),
+    };
+    assert_eq!(error.origin_location(), Some(inner));
+    assert_ne!(error.origin_location(), error.location());
+}
+
+#[test]
+fn test_origin_without_source() {
+    let error = ConfigError::Missing {
+        location: Location::caller(),
+    };
+    assert_eq!(error.origin_location(), error.location());
+    assert_eq!(error.locations().count(), 1);
+}
+
+#[test]
+fn test_locations() {
+    let error = StartupError {
+        source: Box::new(load().unwrap_err()),
+        location: wherror::Location::caller(),
+    };
+    let locations: Vec<_> = error.locations().collect();
+    let types: Vec<_> = locations.iter().map(|(type_name, _)| *type_name).collect();
+    let mut expected = vec![
+        "test_origin_location::ConfigError",
+        "test_origin_location::ReadError",
+    ];
+    if cfg!(any(debug_assertions, feature = "track-location")) {
+        expected.insert(0, "test_origin_location::StartupError");
+    }
+    assert_eq!(types, expected);
+    assert_eq!(
+        error.origin_location(),
+        locations.last().map(|(_, location)| *location),
+    );
+}
+
+#[test]
+fn test_generic_source_not_followed() {
+    let error = GenericError {
+        source: read().unwrap_err(),
+        location: Location::caller(),
+    };
+    assert_eq!(error.locations().count(), 1);
+    assert_eq!(error.origin_location(), error.location());
+}
+
+#[test]
+fn test_boxed() {
+    let error = LoadError::from(load().unwrap_err());
+    let types: Vec<_> = error.locations().map(|(type_name, _)| type_name).collect();
+    assert_eq!(
+        types,
+        [
+            "test_origin_location::LoadError",
+            "test_origin_location::ConfigError",
+            "test_origin_location::ReadError",
+        ],
+    );
+    assert_ne!(error.origin_location(), error.location());
+}
+
+#[test]
+fn test_wrapper_without_location() {
+    let error = AppError::from(load().unwrap_err());
+    let types: Vec<_> = error.locations().map(|(type_name, _)| type_name).collect();
+    assert_eq!(
+        types,
+        [
+            "test_origin_location::ConfigError",
+            "test_origin_location::ReadError",
+        ],
+    );
+    assert_eq!(
+        error.origin_location(),
+        error.locations().last().map(|(_, location)| location)
+    );
+
+    let error = Opaque::from(read().unwrap_err());
+    assert_eq!(error.origin_location(), Some(error.0.location));
+
+    assert_eq!(AppError::Shutdown.origin_location(), None);
+    assert_eq!(AppError::Shutdown.locations().count(), 0);
+}
@@ -0,0 +1,46 @@
+use wherror::{Error, Problem};
+
+#[derive(Error, Debug)]
+#[error(status = 500)]
+enum Error {
+    #[error("no user named \"{0}\"")]
+    #[error(status = 404, code = "user::not_found")]
+    NotFound(String),
+    #[error("unexpected")]
+    Unexpected,
+}
+
+#[test]
+fn test_problem() {
+    let problem = Problem::new(&Error::NotFound("a\\b".to_owned()));
+    assert_eq!("about:blank", problem.problem_type);
+    assert_eq!("Not Found", problem.title);
+    assert_eq!(404, problem.status);
+    assert_eq!(Some("user::not_found"), problem.code);
+    assert_eq!(
+        r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"no user named \"a\\b\"","code":"user::not_found"}"#,
+        problem.to_json(),
+    );
+}
+
+#[test]
+fn test_without_code() {
+    let problem = Problem::new(&Error::Unexpected).with_type("https://example.com/unexpected");
+    assert_eq!(
+        r#"{"type":"https://example.com/unexpected","title":"Internal Server Error","status":500,"detail":"unexpected"}"#,
+        problem.to_json(),
+    );
+}
+
+#[test]
+fn test_control_characters() {
+    #[derive(Error, Debug)]
+    #[error("line\none\u{1}")]
+    #[error(status = 599)]
+    struct Odd;
+
+    assert_eq!(
+        r#"{"type":"about:blank","title":"Unknown Status","status":599,"detail":"line\none\u0001"}"#,
+        Problem::new(&Odd).to_json(),
+    );
+}
@@ -0,0 +1,105 @@
+#![allow(dead_code)]
+
+use std::num::ParseIntError;
+use std::panic::Location;
+use wherror::{Error, ResultExt as _, SetLocation, SourceLocation};
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("invalid port")]
+    Port(#[source] ParseIntError, &'static Location<'static>),
+    #[error("missing key {key}")]
+    Missing {
+        key: &'static str,
+        location: wherror::Location,
+    },
+    #[error("unknown")]
+    Unknown,
+}
+
+#[derive(Error, Debug)]
+#[error("parse failed")]
+pub struct ParseError {
+    #[source]
+    source: ParseIntError,
+    location: SourceLocation,
+}
+
+#[derive(Error, Debug)]
+#[error(boxed)]
+pub enum LoadErrorRepr {
+    #[error("load failed")]
+    Load {
+        location: &'static Location<'static>,
+    },
+}
+
+fn placeholder() -> &'static Location<'static> {
+    Location::caller()
+}
+
+#[test]
+fn test_located() {
+    let result: Result<(), _> = Err(ConfigError::Port(
+        "x".parse::<u16>().unwrap_err(),
+        placeholder(),
+    ));
+    let line = line!() + 1;
+    let error = result.located().unwrap_err();
+    assert_eq!(error.location().unwrap().line(), line);
+    assert_eq!(error.location().unwrap().file(), file!());
+}
+
+#[test]
+fn test_map_err_located() {
+    let line = line!() + 3;
+    let error = "x"
+        .parse::<u16>()
+        .map_err_located(|source| ParseError {
+            source,
+            location: wherror::here!(),
+        })
+        .unwrap_err();
+    assert_eq!(error.location().unwrap().line(), line);
+    assert_eq!(error.location.module_path(), module_path!());
+}
+
+#[test]
+fn test_or_located() {
+    let line = line!() + 3;
+    let error = "x"
+        .parse::<u16>()
+        .or_located(ConfigError::Port)
+        .unwrap_err();
+    assert_eq!(error.location().unwrap().line(), line);
+}
+
+#[test]
+fn test_with_location() {
+    let location = placeholder();
+    let error = ConfigError::Missing {
+        key: "port",
+        location: wherror::Location::caller(),
+    }
+    .with_location(location);
+    if cfg!(any(debug_assertions, feature = "track-location")) {
+        assert_eq!(error.location(), Some(location));
+    } else {
+        assert_eq!(error.location(), None);
+    }
+
+    let mut error = ConfigError::Unknown;
+    error.set_location(location);
+    assert_eq!(error.location(), None);
+}
+
+#[test]
+fn test_boxed() {
+    let location = placeholder();
+    let mut error = Box::new(LoadError::from(LoadErrorRepr::Load {
+        location: Location::caller(),
+    }));
+    SetLocation::set_location(&mut error, location);
+    assert_eq!(error.location(), Some(location));
+    assert_eq!(error.with_location(location).location(), Some(location));
+}
@@ -0,0 +1,117 @@
+use std::io;
+use wherror::{Error, Retryable};
+
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("rate limited")]
+    #[error(retryable)]
+    RateLimited,
+    #[error("server returned {status}")]
+    #[error(retryable = *status >= 500)]
+    Http { status: u16 },
+    #[error("invalid url")]
+    InvalidUrl,
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn test_enum() {
+    assert!(FetchError::RateLimited.is_retryable());
+    assert!(FetchError::Http { status: 503 }.is_retryable());
+    assert!(!FetchError::Http { status: 404 }.is_retryable());
+    assert!(!FetchError::InvalidUrl.is_retryable());
+}
+
+#[test]
+fn test_io() {
+    let timeout = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+    assert!(timeout.is_retryable());
+    assert!(FetchError::from(timeout).is_retryable());
+
+    let not_found = io::Error::new(io::ErrorKind::NotFound, "not found");
+    assert!(!not_found.is_retryable());
+    assert!(!FetchError::from(not_found).is_retryable());
+}
+
+#[test]
+fn test_delegate() {
+    #[derive(Error, Debug)]
+    #[error("not retryable")]
+    struct Opaque;
+
+    #[derive(Error, Debug)]
+    #[error(retryable = false)]
+    enum Error {
+        #[error(transparent)]
+        Fetch(FetchError),
+        #[error("boxed")]
+        Boxed(#[source] Box<FetchError>),
+        #[error("maybe")]
+        Maybe { source: Option<FetchError> },
+        #[error("opaque")]
+        Opaque(#[from] Opaque),
+        #[error("anyhow")]
+        #[error(retryable)]
+        Other(#[source] anyhow::Error),
+    }
+
+    assert!(Error::Fetch(FetchError::RateLimited).is_retryable());
+    assert!(!Error::Fetch(FetchError::InvalidUrl).is_retryable());
+    assert!(Error::Boxed(Box::new(FetchError::RateLimited)).is_retryable());
+    let source = Some(FetchError::RateLimited);
+    assert!(Error::Maybe { source }.is_retryable());
+    assert!(!Error::Maybe { source: None }.is_retryable());
+    assert!(!Error::from(Opaque).is_retryable());
+    assert!(Error::Other(anyhow::anyhow!("oh no")).is_retryable());
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("attempt {attempt} failed")]
+    #[error(retryable = *attempt < 3)]
+    struct Attempt {
+        attempt: u32,
+    }
+
+    assert!(Attempt { attempt: 1 }.is_retryable());
+    assert!(!Retryable::is_retryable(&Attempt { attempt: 3 }));
+}
+
+#[test]
+fn test_enum_default_fields() {
+    #[derive(Error, Debug)]
+    #[error(retryable = *attempts < 3)]
+    enum Error {
+        #[error("timed out after {attempts} attempts")]
+        Timeout { attempts: u32 },
+        #[error("formatting failed after {attempts} attempts")]
+        Fmt {
+            source: std::fmt::Error,
+            attempts: u32,
+        },
+        #[error("io error after {attempts} attempts")]
+        Io { source: io::Error, attempts: u32 },
+    }
+
+    assert!(Error::Timeout { attempts: 1 }.is_retryable());
+    assert!(!Error::Timeout { attempts: 3 }.is_retryable());
+    let source = std::fmt::Error;
+    assert!(Error::Fmt {
+        source,
+        attempts: 2
+    }
+    .is_retryable());
+    assert!(!Error::Fmt {
+        source,
+        attempts: 3
+    }
+    .is_retryable());
+    let source = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+    assert!(Error::Io {
+        source,
+        attempts: 5
+    }
+    .is_retryable());
+}
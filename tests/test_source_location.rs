@@ -0,0 +1,109 @@
+use std::io;
+use wherror::{here, Error, SourceLocation};
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("missing key {key} at {location}")]
+    MissingKey {
+        key: &'static str,
+        location: SourceLocation,
+    },
+    #[error("read failed at {location:short}")]
+    Io {
+        #[from]
+        source: io::Error,
+        location: SourceLocation,
+    },
+    #[error("invalid at {location:full}")]
+    Invalid { location: SourceLocation },
+}
+
+#[derive(Error, Debug)]
+#[error("parse failed at {0:short}")]
+pub struct ParseError(&'static std::panic::Location<'static>);
+
+fn missing_key() -> ConfigError {
+    ConfigError::MissingKey {
+        key: "port",
+        location: here!(),
+    }
+}
+
+#[test]
+fn test_here() {
+    let error = missing_key();
+    let location = error.location().unwrap();
+    assert_eq!(location.file(), file!());
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "missing key port at {}:{}:{}",
+            file!(),
+            location.line(),
+            location.column()
+        ),
+    );
+
+    let ConfigError::MissingKey { location, .. } = error else {
+        unreachable!();
+    };
+    assert_eq!(location.module_path(), module_path!());
+    assert_eq!(
+        location.function(),
+        Some("test_source_location::missing_key"),
+    );
+    assert_eq!(location.crate_name(), Some("wherror"));
+    assert_eq!(location.crate_version(), Some(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn test_here_in_closure() {
+    let capture = || here!();
+    let location = capture();
+    assert_eq!(
+        location.function(),
+        Some("test_source_location::test_here_in_closure"),
+    );
+}
+
+#[test]
+fn test_from() {
+    let line = line!() + 1;
+    let error = ConfigError::from(io::Error::new(io::ErrorKind::Other, "oh no"));
+    assert_eq!(
+        error.to_string(),
+        format!("read failed at test_source_location.rs:{}", line),
+    );
+
+    let ConfigError::Io { location, .. } = error else {
+        unreachable!();
+    };
+    assert_eq!(location.line(), line);
+    assert_eq!(location.module_path(), module_path!());
+    assert_eq!(location.function(), None);
+}
+
+#[test]
+fn test_full() {
+    let error = ConfigError::Invalid { location: here!() };
+    let location = error.location().unwrap();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "invalid at {}:{}:{} in test_source_location::test_full (wherror {})",
+            file!(),
+            location.line(),
+            location.column(),
+            env!("CARGO_PKG_VERSION"),
+        ),
+    );
+}
+
+#[test]
+fn test_short_panic_location() {
+    let error = ParseError(std::panic::Location::caller());
+    assert_eq!(
+        error.to_string(),
+        format!("parse failed at test_source_location.rs:{}", error.0.line()),
+    );
+}
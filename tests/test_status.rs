@@ -0,0 +1,129 @@
+use std::io;
+use wherror::{Error, HttpStatus};
+
+#[derive(Error, Debug)]
+#[error(status = 500)]
+pub enum StoreError {
+    #[error("record {0} not found")]
+    #[error(status = 404, code = "store::not_found")]
+    NotFound(u64),
+    #[error("record is locked")]
+    #[error(status = 423)]
+    Locked,
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn test_enum() {
+    assert_eq!(404, StoreError::NotFound(1).http_status());
+    assert_eq!(
+        Some("store::not_found"),
+        StoreError::NotFound(1).error_code()
+    );
+    assert_eq!(423, StoreError::Locked.http_status());
+    assert_eq!(None, StoreError::Locked.error_code());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    assert_eq!(500, StoreError::from(io).http_status());
+}
+
+#[test]
+fn test_enum_without_default() {
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error("bad request")]
+        #[error(status = 400)]
+        BadRequest,
+        #[error("unexpected")]
+        Unexpected,
+    }
+
+    assert_eq!(400, Error::BadRequest.http_status());
+    assert_eq!(500, Error::Unexpected.http_status());
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Error, Debug)]
+    #[error("too many requests")]
+    #[error(status = 429, code = "rate_limited")]
+    struct RateLimited;
+
+    assert_eq!(429, RateLimited.http_status());
+    assert_eq!(Some("rate_limited"), HttpStatus::error_code(&RateLimited));
+}
+
+#[test]
+fn test_delegate_from() {
+    #[derive(Error, Debug)]
+    #[error(status = 502)]
+    enum ApiError {
+        #[error("store failed")]
+        Store(#[from] StoreError),
+        #[error("io failed")]
+        Io(#[from] io::Error),
+    }
+
+    assert_eq!(404, ApiError::from(StoreError::NotFound(1)).http_status());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    assert_eq!(502, ApiError::from(io).http_status());
+}
+
+#[test]
+fn test_delegate_box_option() {
+    #[derive(Error, Debug)]
+    #[error(status = 500)]
+    enum Boxed {
+        #[error("boxed store failed")]
+        Store(#[from] Box<StoreError>),
+    }
+
+    #[derive(Error, Debug)]
+    #[error(status = 500)]
+    enum Maybe {
+        #[error("maybe store failed")]
+        Store(#[from] Option<StoreError>),
+    }
+
+    assert_eq!(423, Boxed::from(StoreError::Locked).http_status());
+    assert_eq!(423, Maybe::from(StoreError::Locked).http_status());
+    assert_eq!(500, Maybe::Store(None).http_status());
+}
+
+#[test]
+fn test_delegate_transparent() {
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    #[error(status = 500)]
+    struct Wrapper(StoreError);
+
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error(transparent)]
+        Store(StoreError),
+        #[error(transparent)]
+        #[error(status = 503)]
+        Other(anyhow::Error),
+        #[error(transparent)]
+        Io(io::Error),
+    }
+
+    assert_eq!(404, Wrapper(StoreError::NotFound(1)).http_status());
+    assert_eq!(423, Error::Store(StoreError::Locked).http_status());
+    assert_eq!(503, Error::Other(anyhow::anyhow!("oh no")).http_status());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no");
+    assert_eq!(500, Error::Io(io).http_status());
+}
+
+#[test]
+fn test_generic() {
+    #[derive(Error, Debug)]
+    #[error("generic")]
+    #[error(status = 409)]
+    struct Error<T>(T);
+
+    assert_eq!(409, Error(0).http_status());
+}
@@ -0,0 +1,11 @@
+use std::io;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(accessors)]
+pub enum Error {
+    #[error("io error")]
+    Source(#[from] io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(adhoc)]
+    First(String),
+    #[error(adhoc)]
+    Second(String),
+}
+
+fn main() {}
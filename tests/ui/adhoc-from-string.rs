@@ -0,0 +1,11 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("invalid utf-8")]
+    Utf8(#[from] String),
+    #[error(adhoc)]
+    Other(String),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(adhoc)]
+    Other { message: String, key: String },
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(adhoc)]
+pub struct Error(String);
+
+fn main() {}
@@ -0,0 +1,23 @@
+use std::rc::Rc;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(max_size = 8)]
+pub struct TooLarge {
+    buf: [u8; 64],
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(assert(Send, Sync))]
+pub struct NotSend {
+    shared: Rc<str>,
+}
+
+#[derive(Error, Debug)]
+#[error("{0}")]
+#[error(max_size = 8)]
+pub struct Generic<T>(T);
+
+fn main() {}
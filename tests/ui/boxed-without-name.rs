@@ -0,0 +1,10 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(boxed)]
+pub enum MyError {
+    #[error("failed")]
+    Failed,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use std::time::SystemTime;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[error(capture = time)]
+    created: SystemTime,
+    #[error(capture = time)]
+    updated: SystemTime,
+}
+
+fn main() {}
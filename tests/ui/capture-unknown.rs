@@ -0,0 +1,10 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[error(capture = process)]
+    process: u32,
+}
+
+fn main() {}
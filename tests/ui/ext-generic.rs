@@ -0,0 +1,10 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(ext)]
+pub enum Error<T: std::fmt::Debug> {
+    #[error("missing {0:?}")]
+    Missing(T),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(ext)]
+pub struct Error;
+
+fn main() {}
@@ -0,0 +1,16 @@
+use wherror::Error;
+
+mod geo {
+    #[derive(Debug)]
+    pub struct Location;
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[from]
+    source: std::io::Error,
+    location: geo::Location,
+}
+
+fn main() {}
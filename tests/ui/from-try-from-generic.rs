@@ -0,0 +1,10 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("wrapped")]
+pub struct Wrapped<E: std::error::Error + 'static> {
+    #[from(try_from)]
+    source: E,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[from]
+    #[error(id)]
+    source: std::io::Error,
+}
+
+fn main() {}
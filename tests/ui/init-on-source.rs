@@ -0,0 +1,11 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[from]
+    #[error(init = std::fmt::Error)]
+    source: std::fmt::Error,
+}
+
+fn main() {}
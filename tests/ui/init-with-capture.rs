@@ -0,0 +1,11 @@
+use std::time::SystemTime;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[error(capture = time, init = SystemTime::UNIX_EPOCH)]
+    time: SystemTime,
+}
+
+fn main() {}
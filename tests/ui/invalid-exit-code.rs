@@ -0,0 +1,13 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(exit_code = "EX_WHATEVER")]
+pub struct UnknownName;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(exit_code = 256)]
+pub struct OutOfRange;
+
+fn main() {}
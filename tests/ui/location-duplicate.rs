@@ -0,0 +1,13 @@
+use std::panic::Location;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[location]
+    a: &'static Location<'static>,
+    #[location]
+    b: &'static Location<'static>,
+}
+
+fn main() {}
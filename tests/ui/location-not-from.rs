@@ -0,0 +1,12 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("failed at {location}")]
+pub struct Error {
+    #[from]
+    source: std::io::Error,
+    #[location]
+    location: String,
+}
+
+fn main() {}
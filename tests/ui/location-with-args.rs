@@ -0,0 +1,11 @@
+use std::panic::Location;
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[location(caller)]
+    location: &'static Location<'static>,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(capture = thread)]
+pub struct Error;
+
+fn main() {}
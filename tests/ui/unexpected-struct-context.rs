@@ -0,0 +1,8 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[context]
+pub struct Error;
+
+fn main() {}
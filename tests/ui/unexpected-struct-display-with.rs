@@ -0,0 +1,8 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(display_with = std::convert::identity)]
+pub struct Error;
+
+fn main() {}
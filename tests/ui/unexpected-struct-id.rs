@@ -0,0 +1,8 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(id)]
+pub struct Error;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error(init = 0)]
+pub struct Error;
+
+fn main() {}
@@ -0,0 +1,8 @@
+use wherror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[location]
+pub struct Error;
+
+fn main() {}